@@ -0,0 +1,70 @@
+use crate::graphics::open_gl::ErrorType;
+
+/// Errors reported by battery.
+///
+#[derive(Debug)]
+pub enum Error {
+    /// An error reported by SDL, like a failure creating the window or the OpenGL context.
+    Sdl(String),
+
+    /// An error reported by `glGetError`.
+    Gl(ErrorType),
+
+    /// A shader failed to compile, holds the info log.
+    ShaderCompile(String),
+
+    /// A shader program failed to link, holds the info log.
+    ShaderLink(String),
+
+    /// An image could not be loaded or decoded.
+    Image(image::ImageError),
+
+    /// A file could not be read or written.
+    Io(std::io::Error),
+
+    /// Any other error, including the ones returned by the game itself.
+    Other(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Sdl(message)           => write!(f, "SDL error: {}", message),
+            Self::Gl(error)              => write!(f, "OpenGL error: {:?}", error),
+            Self::ShaderCompile(message) => write!(f, "shader compilation failed: {}", message),
+            Self::ShaderLink(message)    => write!(f, "shader linking failed: {}", message),
+            Self::Image(error)           => write!(f, "image error: {}", error),
+            Self::Io(error)              => write!(f, "IO error: {}", error),
+            Self::Other(message)         => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Image(error) => Some(error),
+            Self::Io(error)    => Some(error),
+
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorType> for Error {
+    fn from(error: ErrorType) -> Self {
+        Self::Gl(error)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(error: image::ImageError) -> Self {
+        Self::Image(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
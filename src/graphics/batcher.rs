@@ -1,11 +1,12 @@
-use crate::App;
+use crate::{ App, Error };
 
-use super::{ open_gl, Shader, Vertex, Texture, Quad, Canvas };
-use glam::{ Mat4, Vec2, vec2, vec3 };
+use super::{ open_gl, Shader, Uniform, Vertex, Texture, Quad, Canvas };
+use glam::{ Affine2, Mat2, Mat3, Mat4, Vec2, vec2 };
+use std::rc::Rc;
 
 /// Default Vertex Shader code.
 ///
-const DEFAULT_VERT_CODE: &str = r#"
+pub(crate) const DEFAULT_VERT_CODE: &str = r#"
     #version 330
 
     uniform mat4 u_matrix;
@@ -62,11 +63,78 @@ pub enum BatchModes {
     Texture,
 }
 
+/// How the drawn colors are mixed with the ones already in the render target.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum BlendMode {
+    /// Regular transparency.
+    #[default]
+    Alpha,
+
+    /// Transparency for textures with the colors already multiplied by the alpha.
+    Premultiplied,
+
+    /// Adds the colors, useful for lights and glows.
+    Additive,
+
+    /// Multiplies the colors, useful for shadows and tinting.
+    Multiply,
+
+    /// Brightens the colors, the opposite of `Multiply`.
+    Screen,
+
+    /// Subtracts the drawn colors from the target.
+    Subtract,
+
+    /// Overwrites the target, including its alpha.
+    Replace,
+
+    /// Any other combination, the equation is used for both the colors and the alpha.
+    Custom {
+        equation:  open_gl::BlendEquation,
+        src_color: open_gl::BlendFactor,
+        dst_color: open_gl::BlendFactor,
+        src_alpha: open_gl::BlendFactor,
+        dst_alpha: open_gl::BlendFactor,
+    },
+}
+
+impl BlendMode {
+    /// Sets the OpenGL blend state.
+    ///
+    fn apply(self) -> Result<(), Error> {
+        use open_gl::{ BlendEquation as Eq, BlendFactor as F };
+
+        let (equation, src_color, dst_color, src_alpha, dst_alpha) = match self {
+            Self::Alpha         => (Eq::Add,             F::SrcAlpha,  F::OneMinusSrcAlpha, F::One,      F::OneMinusSrcAlpha),
+            Self::Premultiplied => (Eq::Add,             F::One,       F::OneMinusSrcAlpha, F::One,      F::OneMinusSrcAlpha),
+            Self::Additive      => (Eq::Add,             F::SrcAlpha,  F::One,              F::Zero,     F::One),
+            Self::Multiply      => (Eq::Add,             F::DstColor,  F::Zero,             F::DstAlpha, F::Zero),
+            Self::Screen        => (Eq::Add,             F::One,       F::OneMinusSrcColor, F::One,      F::OneMinusSrcAlpha),
+            Self::Subtract      => (Eq::ReverseSubtract, F::SrcAlpha,  F::One,              F::Zero,     F::One),
+            Self::Replace       => (Eq::Add,             F::One,       F::Zero,             F::One,      F::Zero),
+
+            Self::Custom { equation, src_color, dst_color, src_alpha, dst_alpha } => {
+                (equation, src_color, dst_color, src_alpha, dst_alpha)
+            },
+        };
+
+        open_gl::blend_equation(equation)?;
+        open_gl::blend_func_separate(src_color, dst_color, src_alpha, dst_alpha)
+    }
+}
+
 /// Group of Vertices.
 /// 
 pub struct Batch {
     pub mode:    BatchModes,
     pub texture: Option<u32>,
+    pub blend:   BlendMode,
+    pub shader:  Option<Rc<Shader>>,
+    pub scissor: Option<(i32, i32, i32, i32)>,
+
+    /// Uniforms set before the Batch, given to its shader before drawing it.
+    pub uniforms: Vec<(String, Uniform)>,
 
     pub indices_count: u32,
     pub indices_start: u32,
@@ -74,6 +142,62 @@ pub struct Batch {
     pub elements: u32,
 }
 
+/// Rectangle as `(x, y, width, height)`.
+///
+type Rect = (f32, f32, f32, f32);
+
+/// The render target, used to turn clipping rectangles into scissor boxes.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Target {
+    /// Height in the coordinates the Batcher draws with.
+    height: f32,
+
+    /// Pixels per unit, above 1 on high-DPI displays.
+    scale: (f32, f32),
+
+    /// Whether the y axis goes up in the framebuffer, like in OpenGL, instead of down.
+    flipped: bool,
+}
+
+impl Target {
+    /// Converts a rectangle to a scissor box in framebuffer pixels, with the origin at the bottom-left.
+    ///
+    fn scissor(&self, (x, y, width, height): Rect) -> (i32, i32, i32, i32) {
+        let left   = (x * self.scale.0).round();
+        let right  = ((x + width) * self.scale.0).round();
+        let bottom = match self.flipped {
+            true  => y,
+            false => self.height - y - height,
+        };
+        let top    = ((bottom + height) * self.scale.1).round();
+        let bottom = (bottom * self.scale.1).round();
+
+        (left as i32, bottom as i32, (right - left) as i32, (top - bottom) as i32)
+    }
+}
+
+/// Returns the area shared by two rectangles, it is empty when they don't overlap.
+///
+fn intersect(a: Rect, b: Rect) -> Rect {
+    let left   = a.0.max(b.0);
+    let top    = a.1.max(b.1);
+    let right  = (a.0 + a.2).min(b.0 + b.2);
+    let bottom = (a.1 + a.3).min(b.1 + b.3);
+
+    (left, top, (right - left).max(0.0), (bottom - top).max(0.0))
+}
+
+/// Whether two batches use the same Shader, `None` being the default one.
+///
+fn same_shader(a: &Option<Rc<Shader>>, b: &Option<Rc<Shader>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+        (None, None)       => true,
+        _                  => false,
+    }
+}
+
 /// A 2D Batcher used to draw shapes, images and textures.
 ///
 pub struct Batcher {
@@ -83,34 +207,41 @@ pub struct Batcher {
     vertex_array_object:  u32,
     vertex_buffer_object: u32,
 
-    shader: Shader,
-    matrix: Mat4,
-    color:  (f32, f32, f32, f32),
+    shader:     Shader,
+    projection: Mat4,
+    transform:  Affine2,
+    transforms: Vec<Affine2>,
+    color:      (f32, f32, f32, f32),
+    blend_mode: BlendMode,
+
+    custom_shader: Option<Rc<Shader>>,
+    uniforms:      Vec<(String, Uniform)>,
+
+    target: Target,
+    clip:   Option<Rect>,
+    clips:  Vec<Option<Rect>>,
 }
 
 impl Batcher {
     /// Creates a new Batcher.
     ///
-    pub fn new() -> Self {
-        open_gl::enable(open_gl::EnableCap::Blend).unwrap();
-        open_gl::disable(open_gl::EnableCap::DepthTest).unwrap();
+    pub fn new() -> Result<Self, Error> {
+        open_gl::enable(open_gl::EnableCap::Blend)?;
+        open_gl::disable(open_gl::EnableCap::DepthTest)?;
 
-        open_gl::blend_func(
-            open_gl::BlendFactor::SrcAlpha, 
-            open_gl::BlendFactor::OneMinusSrcAlpha
-        ).unwrap();
+        BlendMode::Alpha.apply()?;
         
         // Creates the default shader.
         let shader = Shader::new(
             &std::ffi::CString::new(DEFAULT_VERT_CODE).unwrap(), 
             &std::ffi::CString::new(DEFAULT_FRAG_CODE).unwrap()
-        ).unwrap();
+        )?;
 
         // Gen vertex buffers.
-        let vertex_array_object  = open_gl::gen_vertex_array().unwrap();
-        let vertex_buffer_object = open_gl::gen_buffer().unwrap();
+        let vertex_array_object  = open_gl::gen_vertex_array()?;
+        let vertex_buffer_object = open_gl::gen_buffer()?;
 
-        Self {
+        Ok(Self {
             vertices: Vec::new(),
             batches:  Vec::new(),
 
@@ -118,71 +249,121 @@ impl Batcher {
             vertex_buffer_object,
 
             shader,
-            matrix: Mat4::IDENTITY,
-            color:  (1.0, 1.0, 1.0, 1.0),
-        }
+            projection: Mat4::IDENTITY,
+            transform:  Affine2::IDENTITY,
+            transforms: Vec::new(),
+            color:      (1.0, 1.0, 1.0, 1.0),
+            blend_mode: BlendMode::Alpha,
+
+            custom_shader: None,
+            uniforms:      Vec::new(),
+
+            target: Target { height: 0.0, scale: (1.0, 1.0), flipped: false },
+            clip:   None,
+            clips:  Vec::new(),
+        })
     }
 
-    /// Prepare the Batcher for rendering.
+    /// Prepare the Batcher for rendering, the transform and clip stacks start empty
+    /// and the uniforms set since the last draw are dropped.
     ///
-    pub fn origin(&mut self, app: &App) {
-        self.viewport(
-            app.get_width()  as f32,
-            app.get_height() as f32,
-        );
+    pub fn origin(&mut self, app: &App) -> Result<(), Error> {
+        self.window_viewport(app)?;
 
         self.vertices.clear();
         self.batches.clear();
+        self.uniforms.clear();
+        self.reset_transform();
+        self.transforms.clear();
+        self.reset_scissor();
+        self.clips.clear();
+
+        Ok(())
     }
 
     /// Presents the drawn contents of the Batcher.
     ///
-    pub fn present(&mut self) {
-        self.shader.use_program();
-
+    pub fn present(&mut self) -> Result<(), Error> {
         if self.vertices.len() > 0 {
             let size    = self.vertices.len() * std::mem::size_of::<Vertex>();
             let pointer = self.vertices.as_ptr();
 
             // Setting up the Vertex Buffer.
-            open_gl::bind_buffer(open_gl::BufferTarget::Array, self.vertex_buffer_object).unwrap();
+            open_gl::bind_buffer(open_gl::BufferTarget::Array, self.vertex_buffer_object)?;
             open_gl::buffer_data(
                 open_gl::BufferTarget::Array, 
                 size    as        gl::types::GLsizeiptr,
                 pointer as *const gl::types::GLvoid, 
                 open_gl::BufferUsage::DynamicDraw
-            ).unwrap();
+            )?;
             
             // Setting up Vertex Attribute pointers.
-            open_gl::bind_buffer(open_gl::BufferTarget::Array, 0).unwrap();
-            open_gl::bind_vertex_array(self.vertex_array_object).unwrap();
-            open_gl::bind_buffer(open_gl::BufferTarget::Array, self.vertex_buffer_object).unwrap();
+            open_gl::bind_buffer(open_gl::BufferTarget::Array, 0)?;
+            open_gl::bind_vertex_array(self.vertex_array_object)?;
+            open_gl::bind_buffer(open_gl::BufferTarget::Array, self.vertex_buffer_object)?;
 
             Vertex::attrib_pointers();
 
-            open_gl::bind_buffer(open_gl::BufferTarget::Array, 0).unwrap();
-            open_gl::bind_vertex_array(0).unwrap();
+            open_gl::bind_buffer(open_gl::BufferTarget::Array, 0)?;
+            open_gl::bind_vertex_array(0)?;
         }
 
         // Loop all batches and draw vertices.
-        let texture_location = open_gl::get_uniform_location(self.shader.prog_id, "u_texture").unwrap();
-        let matrix_location  = open_gl::get_uniform_location(self.shader.prog_id, "u_matrix").unwrap();
-
         if self.batches.len() > 0 {
-            open_gl::bind_vertex_array(self.vertex_array_object).unwrap();
-            open_gl::uniform_matrix_4f(matrix_location, false, &self.matrix.to_cols_array()[0]).unwrap();
-            
+            open_gl::bind_vertex_array(self.vertex_array_object)?;
+
+            let mut blend_mode       = None;
+            let mut program          = None;
+            let mut texture_location = -1;
+            let mut scissor          = None;
+
             for batch in self.batches.iter() {
+                let prog_id = batch.shader.as_ref().map_or(self.shader.prog_id, |shader| shader.prog_id);
+
+                // Every shader gets the matrix when it starts being used.
+                if program != Some(prog_id) {
+                    open_gl::use_program(prog_id)?;
+
+                    let matrix_location = open_gl::get_uniform_location(prog_id, "u_matrix")?;
+
+                    open_gl::uniform_matrix_4f(matrix_location, false, &self.projection.to_cols_array()[0])?;
+
+                    texture_location = open_gl::get_uniform_location(prog_id, "u_texture")?;
+                    program          = Some(prog_id);
+                }
+
+                for (name, uniform) in batch.uniforms.iter() {
+                    uniform.apply(open_gl::get_uniform_location(prog_id, name)?)?;
+                }
+
+                // The blend state only changes between batches that need it.
+                if blend_mode != Some(batch.blend) {
+                    batch.blend.apply()?;
+                    blend_mode = Some(batch.blend);
+                }
+
+                if scissor != batch.scissor {
+                    match batch.scissor {
+                        Some((x, y, width, height)) => {
+                            open_gl::enable(open_gl::EnableCap::ScissorTest)?;
+                            open_gl::scissor(x, y, width, height)?;
+                        },
+                        None => open_gl::disable(open_gl::EnableCap::ScissorTest)?,
+                    }
+
+                    scissor = batch.scissor;
+                }
+
                 // Assign the current texture.
                 match batch.texture {
                     Some(texture) => {
-                        open_gl::active_texture(0).unwrap();
-                        open_gl::bind_texture(open_gl::TextureTarget::Texture2D, texture).unwrap();
+                        open_gl::active_texture(0)?;
+                        open_gl::bind_texture(open_gl::TextureTarget::Texture2D, texture)?;
                         
-                        open_gl::uniform_1i(texture_location, 0).unwrap();
+                        open_gl::uniform_1i(texture_location, 0)?;
                     },
                     None => {
-                        open_gl::bind_texture(open_gl::TextureTarget::Texture2D, 0).unwrap();
+                        open_gl::bind_texture(open_gl::TextureTarget::Texture2D, 0)?;
                     }
                 }
                 
@@ -191,39 +372,204 @@ impl Batcher {
                     open_gl::PrimitiveType::Triangles, 
                     batch.indices_start as i32,
                     batch.indices_count as usize
-                ).unwrap();
+                )?;
+            }
+
+            // Clearing the screen is clipped too.
+            if scissor.is_some() {
+                open_gl::disable(open_gl::EnableCap::ScissorTest)?;
             }
         }
 
         self.vertices.clear();
         self.batches.clear();
+
+        Ok(())
     }
 
     /// Recreates the ortho matrix used for rendering and resize the OpenGL Viewport.
     ///
-    pub fn viewport(&mut self, width: f32, height: f32) {
-        self.matrix = Mat4::orthographic_rh_gl(
+    pub fn viewport(&mut self, width: f32, height: f32) -> Result<(), Error> {
+        self.projection = Self::ortho(width, height);
+        self.target     = Target { height, scale: (1.0, 1.0), flipped: false };
+
+        open_gl::viewport(0, 0, width as i32, height as i32)
+    }
+
+    /// Recreates the ortho matrix in screen coordinates and covers the whole window drawable,
+    /// which is bigger than the window on high-DPI displays.
+    ///
+    fn window_viewport(&mut self, app: &App) -> Result<(), Error> {
+        let (drawable_width, drawable_height) = app.get_drawable_size();
+
+        let (width, height) = (app.get_width() as f32, app.get_height() as f32);
+
+        self.projection = Self::ortho(width, height);
+        self.target     = Target {
+            height,
+            scale:   (drawable_width as f32 / width.max(1.0), drawable_height as f32 / height.max(1.0)),
+            flipped: false,
+        };
+
+        open_gl::viewport(0, 0, drawable_width as i32, drawable_height as i32)
+    }
+
+    /// Returns an ortho matrix with the origin at the top-left corner.
+    ///
+    fn ortho(width: f32, height: f32) -> Mat4 {
+        Mat4::orthographic_rh_gl(
             0.0, 
             width,
             height,
             0.0,
             0.0,
             1.0,
-        );
+        )
+    }
 
-        open_gl::viewport(0, 0, width as i32, height as i32).unwrap();
+    /// Saves the current transform, to be restored by `pop`.
+    ///
+    pub fn push(&mut self) {
+        self.transforms.push(self.transform);
     }
 
-    /// Sets the Matrix translation.
+    /// Restores the transform saved by the last `push`, does nothing when there is none.
+    ///
+    pub fn pop(&mut self) {
+        if let Some(transform) = self.transforms.pop() {
+            self.transform = transform;
+        }
+    }
+
+    /// Moves the coordinate system.
     ///
     pub fn translate(&mut self, x: f32, y: f32) {
-        self.matrix *= Mat4::from_translation(vec3(x, y, 0.0));
+        self.apply_transform(Affine2::from_translation(vec2(x, y)));
     }
 
-    /// Sets the Matrix scale.
+    /// Scales the coordinate system.
     ///
     pub fn scale(&mut self, x: f32, y: f32) {
-        self.matrix *= Mat4::from_scale(vec3(x, y, 1.0));
+        self.apply_transform(Affine2::from_scale(vec2(x, y)));
+    }
+
+    /// Rotates the coordinate system around its origin, the angle is in radians and goes clockwise.
+    ///
+    pub fn rotate(&mut self, angle: f32) {
+        self.apply_transform(Affine2::from_angle(angle));
+    }
+
+    /// Shears the coordinate system, `x` moves the points horizontally by their `y` and the other way around.
+    ///
+    pub fn shear(&mut self, x: f32, y: f32) {
+        self.apply_transform(Affine2::from_mat2(Mat2::from_cols(vec2(1.0, y), vec2(x, 1.0))));
+    }
+
+    /// Multiplies the current transform by the given one, it is applied before the current transform.
+    ///
+    pub fn apply_transform(&mut self, transform: Affine2) {
+        self.transform *= transform;
+    }
+
+    /// Multiplies the current transform by a 2D matrix, the last row is ignored.
+    ///
+    pub fn apply_matrix(&mut self, matrix: Mat3) {
+        self.apply_transform(Affine2::from_mat3(matrix));
+    }
+
+    /// Replaces the current transform.
+    ///
+    pub fn set_transform(&mut self, transform: Affine2) {
+        self.transform = transform;
+    }
+
+    /// Returns the current transform.
+    ///
+    pub fn get_transform(&self) -> Affine2 {
+        self.transform
+    }
+
+    /// Resets the current transform, the saved ones are kept.
+    ///
+    pub fn reset_transform(&mut self) {
+        self.transform = Affine2::IDENTITY;
+    }
+
+    /// Converts a point on the screen, or the canvas, to the current coordinate system.
+    /// Useful to know where the mouse is in a transformed scene.
+    ///
+    pub fn screen_to_local(&self, x: f32, y: f32) -> (f32, f32) {
+        self.transform.inverse().transform_point2(vec2(x, y)).into()
+    }
+
+    /// Converts a point in the current coordinate system to the screen, or the canvas.
+    ///
+    pub fn local_to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        self.transform.transform_point2(vec2(x, y)).into()
+    }
+
+    /// Clips the next draws to a rectangle in the current coordinate system, replacing the current clip.
+    /// A transformed rectangle is clipped by its bounding box, scissors can't be rotated.
+    ///
+    pub fn set_scissor(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.clip = Some(self.to_screen_rect((x, y, width, height)));
+    }
+
+    /// Stops clipping the next draws.
+    ///
+    pub fn reset_scissor(&mut self) {
+        self.clip = None;
+    }
+
+    /// Returns the current clip on the screen, or the canvas, as `(x, y, width, height)`.
+    ///
+    pub fn get_scissor(&self) -> Option<(f32, f32, f32, f32)> {
+        self.clip
+    }
+
+    /// Saves the current clip and narrows it down to its intersection with a rectangle,
+    /// in the current coordinate system. Useful for nested scroll views and panels.
+    ///
+    pub fn push_clip(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let rect = self.to_screen_rect((x, y, width, height));
+
+        self.clips.push(self.clip);
+        self.clip = Some(match self.clip {
+            Some(clip) => intersect(clip, rect),
+            None       => rect,
+        });
+    }
+
+    /// Restores the clip saved by the last `push_clip`, does nothing when there is none.
+    ///
+    pub fn pop_clip(&mut self) {
+        if let Some(clip) = self.clips.pop() {
+            self.clip = clip;
+        }
+    }
+
+    /// Returns the bounding box of a rectangle moved to the screen with the current transform.
+    ///
+    fn to_screen_rect(&self, (x, y, width, height): Rect) -> Rect {
+        let corners = [(x, y), (x + width, y), (x, y + height), (x + width, y + height)]
+            .map(|(x, y)| self.local_to_screen(x, y));
+
+        let left   = corners.iter().map(|corner| corner.0).fold(f32::INFINITY, f32::min);
+        let top    = corners.iter().map(|corner| corner.1).fold(f32::INFINITY, f32::min);
+        let right  = corners.iter().map(|corner| corner.0).fold(f32::NEG_INFINITY, f32::max);
+        let bottom = corners.iter().map(|corner| corner.1).fold(f32::NEG_INFINITY, f32::max);
+
+        (left, top, right - left, bottom - top)
+    }
+
+    /// Moves a vertex to the screen with the current transform.
+    ///
+    fn transform_vertex(&self, mut vertex: Vertex) -> Vertex {
+        let (x, y) = self.local_to_screen(vertex.position.num1, vertex.position.num2);
+
+        vertex.position.num1 = x;
+        vertex.position.num2 = y;
+        vertex
     }
 
     /// Sets the active draw color.
@@ -238,32 +584,85 @@ impl Batcher {
         self.color.clone()
     }
 
+    /// Sets the blend mode of the next draws, a different mode starts a new batch.
+    ///
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    /// Returns the blend mode of the next draws.
+    ///
+    pub fn get_blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Draws the next shapes and textures with the given Shader, the batches keep it alive until they are presented.
+    /// See `Shader::from_fragment`.
+    ///
+    pub fn set_shader(&mut self, shader: &Rc<Shader>) {
+        self.custom_shader = Some(Rc::clone(shader));
+    }
+
+    /// Draws the next shapes and textures with the default Shader.
+    ///
+    pub fn reset_shader(&mut self) {
+        self.custom_shader = None;
+    }
+
+    /// Sets a uniform of the Shader used by the next draws, the draws before keep the previous value.
+    /// The value is kept by the Shader until it is set again.
+    ///
+    /// ```ignore
+    /// let dissolve = Rc::new(Shader::from_fragment(DISSOLVE_CODE)?);
+    /// let noise    = Rc::new(Texture::new()?.from_path("noise.png")?);
+    ///
+    /// batcher.set_shader(&dissolve);
+    /// batcher.set_uniform("u_threshold", 0.5);
+    /// batcher.set_uniform("u_noise", Uniform::texture(&noise, 0));
+    /// ```
+    ///
+    pub fn set_uniform(&mut self, name: &str, value: impl Into<Uniform>) {
+        self.uniforms.push((name.to_string(), value.into()));
+    }
+
     /// Sets the current render target.
     ///
-    pub fn set_canvas(&mut self, canvas: &Canvas) {
-        self.present();
-        self.viewport(canvas.get_width() as f32, canvas.get_height() as f32);
+    pub fn set_canvas(&mut self, canvas: &Canvas) -> Result<(), Error> {
+        self.present()?;
+        self.viewport(canvas.get_width() as f32, canvas.get_height() as f32)?;
 
-        // Flips vertically the ortho matrix.
-        self.scale(1.0, -1.0);
-        self.translate(0.0, -(canvas.get_height() as f32));
+        // Flips vertically the ortho matrix, the transform is kept.
+        self.projection *= Mat4::from_scale((1.0, -1.0, 1.0).into());
+        self.projection *= Mat4::from_translation((0.0, -(canvas.get_height() as f32), 0.0).into());
+        self.target.flipped = true;
 
-        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, canvas.handle).unwrap();
+        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, canvas.handle)
     }
 
     /// Resets the current render target.
     ///
-    pub fn reset_canvas(&mut self, app: &App) {
-        self.present();
-        self.viewport(app.get_width() as f32, app.get_height() as f32);
+    pub fn reset_canvas(&mut self, app: &App) -> Result<(), Error> {
+        self.present()?;
+        self.window_viewport(app)?;
 
-        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, 0).unwrap();
+        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, 0)
     }
 
     /// Returns a valid Batch structure.
     /// 
     fn get_batch(&mut self, mode: BatchModes, texture: Option<u32>) -> &mut Batch {
-        if self.batches.len() <= 0 || self.batches[self.batches.len() - 1].mode != mode {
+        let scissor = self.clip.map(|clip| self.target.scissor(clip));
+
+        // Uniforms waiting to be set always start a new batch.
+        let compatible = self.uniforms.is_empty() && self.batches.last().is_some_and(|batch| {
+            batch.mode == mode
+                && batch.texture == texture
+                && batch.blend == self.blend_mode
+                && same_shader(&batch.shader, &self.custom_shader)
+                && batch.scissor == scissor
+        });
+
+        if !compatible {
             // If there is no compatible batch, creates a new.
             let mut indices_start = 0;
 
@@ -274,6 +673,10 @@ impl Batcher {
             self.batches.push(Batch {
                 mode,
                 texture,
+                blend:         self.blend_mode,
+                shader:        self.custom_shader.clone(),
+                scissor,
+                uniforms:      std::mem::take(&mut self.uniforms),
                 elements:      0,
                 indices_count: 0,
                 indices_start,
@@ -290,6 +693,7 @@ impl Batcher {
     /// 
     #[inline]
     pub fn push_tri(&mut self, texture: Option<u32>, mode: BatchModes, v0: Vertex, v1: Vertex, v2: Vertex) {
+        let (v0, v1, v2) = (self.transform_vertex(v0), self.transform_vertex(v1), self.transform_vertex(v2));
         let mut batch = self.get_batch(mode, texture);
 
         // Updates the vertex count.
@@ -306,6 +710,8 @@ impl Batcher {
     /// 
     #[inline]
     pub fn push_quad(&mut self, texture: Option<u32>, mode: BatchModes, v0: Vertex, v1: Vertex, v2: Vertex, v3: Vertex) {
+        let (v0, v1) = (self.transform_vertex(v0), self.transform_vertex(v1));
+        let (v2, v3) = (self.transform_vertex(v2), self.transform_vertex(v3));
         let mut batch = self.get_batch(mode, texture);
 
         // Updates the vertex count.
@@ -489,4 +895,21 @@ impl Batcher {
     ) {
         self.texture(&canvas.texture, x, y, quad, angle, scale, origin);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clips_become_scissor_boxes() {
+        assert_eq!(intersect((0.0, 0.0, 100.0, 50.0), (50.0, 25.0, 100.0, 100.0)), (50.0, 25.0, 50.0, 25.0));
+        assert_eq!(intersect((0.0, 0.0, 10.0, 10.0), (20.0, 0.0, 10.0, 10.0)).2, 0.0);
+
+        let window = Target { height: 100.0, scale: (2.0, 2.0), flipped: false };
+        let canvas = Target { height: 100.0, scale: (1.0, 1.0), flipped: true };
+
+        assert_eq!(window.scissor((10.0, 20.0, 30.0, 40.0)), (20, 80, 60, 80));
+        assert_eq!(canvas.scissor((10.0, 20.0, 30.0, 40.0)), (10, 20, 30, 40));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::graphics::Batcher;
+use glam::{ Affine2, Vec2, vec2 };
+
+/// A view over a 2D world, it produces the transform the Batcher draws the world with.
+///
+/// The position is the point of the world shown at the center of the viewport.
+///
+/// ```ignore
+/// camera.follow(player.x, player.y, app.timer.get_update_delta());
+/// camera.update(app.timer.get_update_delta());
+///
+/// camera.attach(&mut batcher);
+/// // Draw the world...
+/// camera.detach(&mut batcher);
+///
+/// let (x, y) = camera.screen_to_world(app.input.get_mouse_position());
+/// ```
+///
+pub struct Camera2D {
+    position: (f32, f32),
+    zoom:     f32,
+    rotation: f32,
+    viewport: (f32, f32),
+    bounds:   Option<(f32, f32, f32, f32)>,
+
+    deadzone:  (f32, f32),
+    smoothing: f32,
+
+    trauma:       f32,
+    trauma_decay: f32,
+    shake_offset: f32,
+    shake_angle:  f32,
+    shake_speed:  f32,
+    shake_time:   f32,
+}
+
+impl Camera2D {
+    /// Creates a Camera for a viewport of the given size, like the window size, looking at the world origin.
+    ///
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            position: (0.0, 0.0),
+            zoom:     1.0,
+            rotation: 0.0,
+            viewport: (width, height),
+            bounds:   None,
+
+            deadzone:  (0.0, 0.0),
+            smoothing: 0.0,
+
+            trauma:       0.0,
+            trauma_decay: 1.0,
+            shake_offset: 16.0,
+            shake_angle:  0.1,
+            shake_speed:  30.0,
+            shake_time:   0.0,
+        }
+    }
+
+    /// Moves the Camera to look at the given point.
+    ///
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.position = (x, y);
+        self.clamp();
+    }
+
+    /// Returns the point the Camera looks at.
+    ///
+    pub fn get_position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    /// Sets the zoom, values above 1 make the world bigger.
+    ///
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+        self.clamp();
+    }
+
+    /// Returns the zoom.
+    ///
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Sets the rotation in radians, the world turns the other way.
+    ///
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the rotation in radians.
+    ///
+    pub fn get_rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Sets the size of the area the Camera draws to, like the window or the canvas size.
+    ///
+    pub fn set_viewport(&mut self, width: f32, height: f32) {
+        self.viewport = (width, height);
+        self.clamp();
+    }
+
+    /// Returns the size of the area the Camera draws to.
+    ///
+    pub fn get_viewport(&self) -> (f32, f32) {
+        self.viewport
+    }
+
+    /// Keeps the view inside the given area of the world, a view bigger than the area is centered on it.
+    /// The rotation and the shake are not taken into account.
+    ///
+    pub fn set_bounds(&mut self, bounds: Option<(f32, f32, f32, f32)>) {
+        self.bounds = bounds;
+        self.clamp();
+    }
+
+    /// Returns the area of the world the view is kept in, as `(x, y, width, height)`.
+    ///
+    pub fn get_bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        self.bounds
+    }
+
+    /// Returns the area of the world in view, as `(x, y, width, height)`, without the rotation.
+    ///
+    pub fn get_visible_area(&self) -> (f32, f32, f32, f32) {
+        let width  = self.viewport.0 / self.zoom;
+        let height = self.viewport.1 / self.zoom;
+
+        (self.position.0 - width / 2.0, self.position.1 - height / 2.0, width, height)
+    }
+
+    /// Sets the size in world units of the area around the position where a followed target moves freely.
+    ///
+    pub fn set_deadzone(&mut self, width: f32, height: f32) {
+        self.deadzone = (width.max(0.0), height.max(0.0));
+    }
+
+    /// Sets how smoothly the Camera follows, roughly the seconds it takes to cover two thirds of the way.
+    /// A smoothing of 0 follows the target instantly.
+    ///
+    pub fn set_smoothing(&mut self, seconds: f32) {
+        self.smoothing = seconds.max(0.0);
+    }
+
+    /// Moves the Camera towards a target, once it leaves the deadzone.
+    ///
+    pub fn follow(&mut self, x: f32, y: f32, delta_time: f64) {
+        let goal = (
+            follow_axis(self.position.0, x, self.deadzone.0 / 2.0),
+            follow_axis(self.position.1, y, self.deadzone.1 / 2.0),
+        );
+
+        // Exponential smoothing doesn't depend on the frame rate.
+        let t = if self.smoothing > 0.0 { 1.0 - (-delta_time as f32 / self.smoothing).exp() } else { 1.0 };
+
+        self.position.0 += (goal.0 - self.position.0) * t;
+        self.position.1 += (goal.1 - self.position.1) * t;
+        self.clamp();
+    }
+
+    /// Adds trauma, which shakes the Camera until it decays. The trauma goes from 0 to 1.
+    ///
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// Returns the current trauma.
+    ///
+    pub fn get_trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Sets the trauma lost per second.
+    ///
+    pub fn set_trauma_decay(&mut self, decay: f32) {
+        self.trauma_decay = decay.max(0.0);
+    }
+
+    /// Sets the strongest shake, reached at full trauma, and how fast it moves.
+    ///
+    pub fn set_shake(&mut self, max_offset: f32, max_angle: f32, speed: f32) {
+        self.shake_offset = max_offset;
+        self.shake_angle  = max_angle;
+        self.shake_speed  = speed;
+    }
+
+    /// Updates the shake.
+    ///
+    pub fn update(&mut self, delta_time: f64) {
+        self.trauma      = (self.trauma - self.trauma_decay * delta_time as f32).max(0.0);
+        self.shake_time += delta_time as f32;
+    }
+
+    /// Returns the transform from the world to the viewport, including the shake.
+    ///
+    pub fn get_transform(&self) -> Affine2 {
+        // The shake grows with the square of the trauma, so small hits are subtle.
+        let shake = self.trauma * self.trauma;
+        let time  = self.shake_time * self.shake_speed;
+
+        let offset = vec2(noise(time, 0.0), noise(time, 10.0)) * self.shake_offset * shake;
+        let angle  = noise(time, 20.0) * self.shake_angle * shake;
+
+        Affine2::from_translation(Vec2::from(self.viewport) / 2.0 + offset)
+            * Affine2::from_angle(-(self.rotation + angle))
+            * Affine2::from_scale(Vec2::splat(self.zoom))
+            * Affine2::from_translation(-Vec2::from(self.position))
+    }
+
+    /// Converts a point of the viewport, like the mouse position, to the world.
+    ///
+    pub fn screen_to_world(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        self.get_transform().inverse().transform_point2(vec2(x, y)).into()
+    }
+
+    /// Converts a point of the world to the viewport.
+    ///
+    pub fn world_to_screen(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        self.get_transform().transform_point2(vec2(x, y)).into()
+    }
+
+    /// Starts drawing through the Camera, saving the current transform of the Batcher.
+    ///
+    pub fn attach(&self, batcher: &mut Batcher) {
+        batcher.push();
+        batcher.apply_transform(self.get_transform());
+    }
+
+    /// Stops drawing through the Camera, restoring the transform saved by `attach`.
+    ///
+    pub fn detach(&self, batcher: &mut Batcher) {
+        batcher.pop();
+    }
+
+    /// Keeps the view inside the bounds.
+    ///
+    fn clamp(&mut self) {
+        if let Some((x, y, width, height)) = self.bounds {
+            let half_width  = self.viewport.0 / self.zoom / 2.0;
+            let half_height = self.viewport.1 / self.zoom / 2.0;
+
+            self.position.0 = clamp_axis(self.position.0, x + half_width,  x + width  - half_width);
+            self.position.1 = clamp_axis(self.position.1, y + half_height, y + height - half_height);
+        }
+    }
+}
+
+/// Returns where the Camera must be on an axis to keep the target within `half` units.
+///
+fn follow_axis(position: f32, target: f32, half: f32) -> f32 {
+    position.clamp(target - half, target + half)
+}
+
+/// Clamps a position, or centers it when the range is reversed because the view is bigger than the bounds.
+///
+fn clamp_axis(position: f32, min: f32, max: f32) -> f32 {
+    if min > max { (min + max) / 2.0 } else { position.clamp(min, max) }
+}
+
+/// Smooth noise between -1 and 1, made of sine waves that don't repeat for a long time.
+///
+fn noise(time: f32, seed: f32) -> f32 {
+    ((time + seed).sin() + (time * 2.3 + seed * 1.7).sin() * 0.5 + (time * 4.1 + seed * 3.1).sin() * 0.25) / 1.75
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: (f32, f32), b: (f32, f32)) {
+        assert!((a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn follows_and_stays_in_bounds() {
+        let mut camera = Camera2D::new(200.0, 100.0);
+
+        camera.set_deadzone(20.0, 20.0);
+        camera.follow(5.0, -5.0, 1.0 / 60.0);
+        assert_eq!(camera.get_position(), (0.0, 0.0));
+
+        camera.follow(30.0, 0.0, 1.0 / 60.0);
+        assert_eq!(camera.get_position(), (20.0, 0.0));
+
+        camera.set_bounds(Some((0.0, 0.0, 400.0, 50.0)));
+        assert_eq!(camera.get_position(), (100.0, 25.0));
+
+        camera.set_zoom(2.0);
+        camera.set_position(1000.0, 0.0);
+        assert_eq!(camera.get_position(), (350.0, 25.0));
+    }
+
+    #[test]
+    fn converts_between_screen_and_world() {
+        let mut camera = Camera2D::new(200.0, 100.0);
+
+        camera.set_position(50.0, 50.0);
+        camera.set_zoom(2.0);
+        assert_near(camera.world_to_screen((50.0, 50.0)), (100.0, 50.0));
+        assert_near(camera.screen_to_world((0.0, 0.0)), (0.0, 25.0));
+
+        camera.set_rotation(1.0);
+        camera.add_trauma(0.5);
+        camera.update(0.25);
+
+        let screen = camera.world_to_screen((12.0, 34.0));
+        assert_near(camera.screen_to_world(screen), (12.0, 34.0));
+    }
+}
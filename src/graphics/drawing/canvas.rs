@@ -1,4 +1,5 @@
 use crate::graphics::{ open_gl, Texture };
+use crate::Error;
 use image::DynamicImage;
 
 /// Used for off-screen rendering, a invisible surface that you can draw to but that will be 
@@ -12,13 +13,13 @@ pub struct Canvas {
 impl Canvas {
     /// Creates a new Canvas.
     ///
-    pub fn new(width: u32, height: u32) -> Self {
+    pub fn new(width: u32, height: u32) -> Result<Self, Error> {
         let bitmap  = DynamicImage::new_rgba8(width, height);
-        let texture = Texture::new().from_dynamic_image(bitmap);
+        let texture = Texture::new()?.from_dynamic_image(bitmap)?;
 
         // Gera o framebuffer.
-        let handle = open_gl::gen_framebuffer().unwrap();
-        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, handle).unwrap();
+        let handle = open_gl::gen_framebuffer()?;
+        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, handle)?;
         unsafe {
             gl::FramebufferTexture2D(
                 gl::FRAMEBUFFER,
@@ -30,12 +31,12 @@ impl Canvas {
 
             gl::DrawBuffer(gl::COLOR_ATTACHMENT0);
         }
-        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, 0).unwrap();
+        open_gl::bind_framebuffer(open_gl::FramebufferTarget::Framebuffer, 0)?;
 
-        Self {
+        Ok(Self {
             handle,
             texture,
-        }
+        })
     }
 
     /// Returns the width of the Canvas.
@@ -53,6 +54,6 @@ impl Canvas {
 
 impl Drop for Canvas {
     fn drop(&mut self) {
-        open_gl::delete_framebuffer(self.handle).unwrap();
+        let _ = open_gl::delete_framebuffer(self.handle);
     }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 pub mod rendering;
-pub use rendering::{ open_gl, Shader, Vertex };
+pub use rendering::{ open_gl, Shader, Uniform, Vertex };
 
 pub mod drawing;
 pub use drawing::{ Quad, Canvas };
@@ -8,4 +8,7 @@ pub mod texture;
 pub use texture::Texture;
 
 pub mod batcher;
-pub use batcher::Batcher;
\ No newline at end of file
+pub use batcher::{ Batcher, BlendMode };
+
+pub mod camera;
+pub use camera::Camera2D;
@@ -1,7 +1,7 @@
 pub mod open_gl;
 
 pub mod shader;
-pub use shader::Shader;
+pub use shader::{ Shader, Uniform };
 
 pub mod vertex;
 pub use vertex::Vertex;
\ No newline at end of file
@@ -3,6 +3,7 @@
 ///
 
 extern crate gl;
+use crate::Error;
 use std::{os::raw::c_void, ffi::CString};
 
 pub fn load<F: Fn(&'static str) -> *const c_void>(load_fn: F) {
@@ -367,7 +368,7 @@ pub enum UniformType {
     SamplerCube = 0x8B60,
 }
 impl UniformType {
-    pub fn from(val: u32) -> Result<UniformType, String> {
+    pub fn from(val: u32) -> Result<UniformType, Error> {
         match val {
             0x8B56 => Ok(Self::Bool),
             0x1404 => Ok(Self::Int),
@@ -379,7 +380,7 @@ impl UniformType {
             0x8B5C => Ok(Self::Mat4),
             0x8B5E => Ok(Self::Sampler2D),
             0x8B60 => Ok(Self::SamplerCube),
-            _ => Err("unknown uniform type".to_string()),
+            _ => Err(Error::Other(format!("unsupported uniform type: {:#x}", val))),
         }
     }
 }
@@ -410,19 +411,18 @@ pub enum ClearMode {
 }
 
 #[inline]
-fn check_error() -> Result<(), String> {
+fn check_error() -> Result<(), Error> {
     let err_code = unsafe { gl::GetError() };
     let err_type = ErrorType::from(err_code);
     if err_type == ErrorType::NoError {
         Ok(())
     } else {
-        let err_str = format!("{:?}", err_type);
-        Err(err_str)
+        Err(Error::Gl(err_type))
     }
 }
 
 #[inline]
-fn get_integer_v(name: u32) -> Result<i32, String> {
+fn get_integer_v(name: u32) -> Result<i32, Error> {
     let mut val: i32 = 0;
     unsafe { gl::GetIntegerv(name, &mut val as *mut i32) };
     check_error()?;
@@ -430,117 +430,123 @@ fn get_integer_v(name: u32) -> Result<i32, String> {
 }
 
 #[inline]
-pub fn version_str() -> Result<String, String> {
+pub fn version_str() -> Result<String, Error> {
     let major = major_version()?;
     let minor = minor_version()?;
     Ok(format!("{}.{}", major, minor))
 }
 
 #[inline]
-pub fn major_version() -> Result<i32, String> {
+pub fn major_version() -> Result<i32, Error> {
     get_integer_v(0x821B)
 }
 
 #[inline]
-pub fn minor_version() -> Result<i32, String> {
+pub fn minor_version() -> Result<i32, Error> {
     get_integer_v(0x821C)
 }
 
 #[inline]
-pub fn max_color_attachments() -> Result<usize, String> {
+pub fn max_color_attachments() -> Result<usize, Error> {
     get_integer_v(0x8CDF).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_cube_map_texture_size() -> Result<usize, String> {
+pub fn max_cube_map_texture_size() -> Result<usize, Error> {
     get_integer_v(0x851C).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_draw_buffers() -> Result<usize, String> {
+pub fn max_draw_buffers() -> Result<usize, Error> {
     get_integer_v(0x8824).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_element_indices() -> Result<usize, String> {
+pub fn max_element_indices() -> Result<usize, Error> {
     get_integer_v(0x80E9).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_element_vertices() -> Result<usize, String> {
+pub fn max_element_vertices() -> Result<usize, Error> {
     get_integer_v(0x80E8).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_renderbuffer_size() -> Result<usize, String> {
+pub fn max_renderbuffer_size() -> Result<usize, Error> {
     get_integer_v(0x84E8).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_samples() -> Result<usize, String> {
+pub fn max_samples() -> Result<usize, Error> {
     get_integer_v(0x8D57).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_texture_size() -> Result<usize, String> {
+pub fn max_texture_size() -> Result<usize, Error> {
     get_integer_v(0x0D33).map(|n| n as usize)
 }
 
 #[inline]
-pub fn max_texture_image_units() -> Result<usize, String> {
+pub fn max_texture_image_units() -> Result<usize, Error> {
     get_integer_v(0x8872).map(|n| n as usize)
 }
 
 #[inline]
-pub fn enable(mode: EnableCap) -> Result<(), String> {
+pub fn enable(mode: EnableCap) -> Result<(), Error> {
     unsafe { gl::Enable(mode as u32) };
     check_error()
 }
 
 #[inline]
-pub fn disable(mode: EnableCap) -> Result<(), String> {
+pub fn disable(mode: EnableCap) -> Result<(), Error> {
     unsafe { gl::Disable(mode as u32) };
     check_error()
 }
 
 #[inline]
-pub fn clear(mode: ClearMode) -> Result<(), String> {
+pub fn clear(mode: ClearMode) -> Result<(), Error> {
     unsafe { gl::Clear(mode as u32) };
     check_error()
 }
 
 #[inline]
-pub fn clear_color(r: f32, g: f32, b: f32, a: f32) -> Result<(), String> {
+pub fn clear_color(r: f32, g: f32, b: f32, a: f32) -> Result<(), Error> {
     unsafe { gl::ClearColor(r, g, b, a) };
     check_error()
 }
 
 #[inline]
-pub fn cull_face(face: CullFace) -> Result<(), String> {
+pub fn cull_face(face: CullFace) -> Result<(), Error> {
     unsafe { gl::CullFace(face as u32) };
     check_error()
 }
 
 #[inline]
-pub fn front_face(face: FrontFace) -> Result<(), String> {
+pub fn front_face(face: FrontFace) -> Result<(), Error> {
     unsafe { gl::FrontFace(face as u32) };
     check_error()
 }
 
 #[inline]
-pub fn blend_equation(eq: BlendEquation) -> Result<(), String> {
+pub fn blend_equation(eq: BlendEquation) -> Result<(), Error> {
     unsafe { gl::BlendEquation(eq as u32) };
     check_error()
 }
 
 #[inline]
-pub fn blend_func(src: BlendFactor, dst: BlendFactor) -> Result<(), String> {
+pub fn blend_func(src: BlendFactor, dst: BlendFactor) -> Result<(), Error> {
     unsafe { gl::BlendFunc(src as u32, dst as u32) };
     check_error()
 }
 
 #[inline]
-pub fn gen_texture() -> Result<u32, String> {
+pub fn blend_func_separate(src_rgb: BlendFactor, dst_rgb: BlendFactor, src_alpha: BlendFactor, dst_alpha: BlendFactor) -> Result<(), Error> {
+    unsafe { gl::BlendFuncSeparate(src_rgb as u32, dst_rgb as u32, src_alpha as u32, dst_alpha as u32) };
+    check_error()
+}
+
+#[inline]
+pub fn gen_texture() -> Result<u32, Error> {
     let mut tex: u32 = 0;
     unsafe { gl::GenTextures(1, &mut tex as *mut u32) };
     check_error()?;
@@ -548,31 +554,31 @@ pub fn gen_texture() -> Result<u32, String> {
 }
 
 #[inline]
-pub fn delete_texture(tex: u32) -> Result<(), String> {
+pub fn delete_texture(tex: u32) -> Result<(), Error> {
     unsafe { gl::DeleteTextures(1, &tex as *const u32) };
     check_error()
 }
 
 #[inline]
-pub fn active_texture(unit: u32) -> Result<(), String> {
+pub fn active_texture(unit: u32) -> Result<(), Error> {
     unsafe { gl::ActiveTexture(0x84C0 + unit) };
     check_error()
 }
 
 #[inline]
-pub fn bind_texture(target: TextureTarget, tex: u32) -> Result<(), String> {
+pub fn bind_texture(target: TextureTarget, tex: u32) -> Result<(), Error> {
     unsafe { gl::BindTexture(target as u32, tex) };
     check_error()
 }
 
 #[inline]
-pub fn tex_parameter_i(target: TextureTarget, name: TextureParam, param: i32) -> Result<(), String> {
+pub fn tex_parameter_i(target: TextureTarget, name: TextureParam, param: i32) -> Result<(), Error> {
     unsafe { gl::TexParameteri(target as u32, name as u32, param) };
     check_error()
 }
 
 #[inline]
-pub fn get_tex_parameter_i(target: TextureTarget, name: TextureParam) -> Result<i32, String> {
+pub fn get_tex_parameter_i(target: TextureTarget, name: TextureParam) -> Result<i32, Error> {
     let mut val: i32 = 0;
     unsafe { gl::GetTexParameteriv(target as u32, name as u32, &mut val as *mut i32) };
     check_error()?;
@@ -590,10 +596,12 @@ pub fn tex_image_2d(
     format: PixelFormat, 
     ty: PixelType,
     data: &[u8]
-) -> Result<(), String> {
-    let min_size = format.component_count() * ty.size_in_bytes();
+) -> Result<(), Error> {
+    let min_size = width.max(0) as usize * height.max(0) as usize * format.component_count() * ty.size_in_bytes();
     if data.len() < min_size {
-        return Err("not enough data to fill texture".to_string());
+        return Err(Error::Other(format!(
+            "texture data is {} bytes, a {}x{} image needs at least {}", data.len(), width, height, min_size
+        )));
     }
     unsafe {
         gl::TexImage2D(
@@ -618,7 +626,7 @@ pub fn get_tex_image(
     format: TextureFormat, 
     ty: PixelType,
     data: &mut Vec<u8>
-) -> Result<(), String> {
+) -> Result<(), Error> {
     let max_size = get_integer_v(0x0D33)? as usize;
     let vec_size = max_size * 4 * 4;
 
@@ -629,7 +637,7 @@ pub fn get_tex_image(
 }*/
 
 #[inline]
-pub fn gen_framebuffer() -> Result<u32, String> {
+pub fn gen_framebuffer() -> Result<u32, Error> {
     let mut fbo: u32 = 0;
     unsafe { gl::GenFramebuffers(1, &mut fbo as *mut u32) };
     check_error()?;
@@ -637,25 +645,25 @@ pub fn gen_framebuffer() -> Result<u32, String> {
 }
 
 #[inline]
-pub fn delete_framebuffer(fbo: u32) -> Result<(), String> {
+pub fn delete_framebuffer(fbo: u32) -> Result<(), Error> {
     unsafe { gl::DeleteFramebuffers(1, &fbo as *const u32) };
     check_error()
 }
 
 #[inline]
-pub fn bind_framebuffer(target: FramebufferTarget, fbo: u32) -> Result<(), String> {
+pub fn bind_framebuffer(target: FramebufferTarget, fbo: u32) -> Result<(), Error> {
     unsafe { gl::BindFramebuffer(target as u32, fbo) };
     check_error()
 }
 
 #[inline]
-pub fn draw_buffers(bufs: &[DrawBuffer]) -> Result<(), String> {
+pub fn draw_buffers(bufs: &[DrawBuffer]) -> Result<(), Error> {
     unsafe { gl::DrawBuffers(bufs.len() as i32, bufs.as_ptr() as *const u32) };
     check_error()
 }
 
 #[inline]
-pub fn read_buffer(buffer: ReadBuffer) -> Result<(), String> {
+pub fn read_buffer(buffer: ReadBuffer) -> Result<(), Error> {
     unsafe { gl::ReadBuffer(buffer as u32) };
     check_error()
 }
@@ -668,7 +676,7 @@ pub fn vertex_attrib_pointer(
     normalized: bool,
     stride: i32,
     pointer_offset: usize
-) -> Result<(), String> {
+) -> Result<(), Error> {
     unsafe {
         gl::VertexAttribPointer(
             index,
@@ -683,19 +691,19 @@ pub fn vertex_attrib_pointer(
 }
 
 #[inline]
-pub fn enable_vertex_attrib_array(index: u32) -> Result<(), String> {
+pub fn enable_vertex_attrib_array(index: u32) -> Result<(), Error> {
     unsafe { gl::EnableVertexAttribArray(index) };
     check_error()
 }
 
 #[inline]
-pub fn disable_vertex_attrib_array(index: u32) -> Result<(), String> {
+pub fn disable_vertex_attrib_array(index: u32) -> Result<(), Error> {
     unsafe { gl::DisableVertexAttribArray(index) };
     check_error()
 }
 
 #[inline]
-pub fn gen_buffer() -> Result<u32, String> {
+pub fn gen_buffer() -> Result<u32, Error> {
     let mut buf: u32 = 0;
     unsafe { gl::GenBuffers(1, &mut buf as *mut u32) };
     check_error()?;
@@ -703,19 +711,19 @@ pub fn gen_buffer() -> Result<u32, String> {
 }
 
 #[inline]
-pub fn delete_buffer(buffer: u32) -> Result<(), String> {
+pub fn delete_buffer(buffer: u32) -> Result<(), Error> {
     unsafe { gl::DeleteBuffers(1, &buffer as *const u32) };
     check_error()
 }
 
 #[inline]
-pub fn bind_buffer(target: BufferTarget, buffer: u32) -> Result<(), String> {
+pub fn bind_buffer(target: BufferTarget, buffer: u32) -> Result<(), Error> {
     unsafe { gl::BindBuffer(target as u32, buffer) };
     check_error()
 }
 
 #[inline]
-pub fn gen_vertex_array() -> Result<u32, String> {
+pub fn gen_vertex_array() -> Result<u32, Error> {
     let mut arr: u32 = 0;
     unsafe { gl::GenVertexArrays(1, &mut arr as *mut u32) };
     check_error()?;
@@ -723,50 +731,50 @@ pub fn gen_vertex_array() -> Result<u32, String> {
 }
 
 #[inline]
-pub fn delete_vertex_array(arr: u32) -> Result<(), String> {
+pub fn delete_vertex_array(arr: u32) -> Result<(), Error> {
     unsafe { gl::DeleteVertexArrays(1, &arr as *const u32) };
     check_error()
 }
 
 #[inline]
-pub fn bind_vertex_array(arr: u32) -> Result<(), String> {
+pub fn bind_vertex_array(arr: u32) -> Result<(), Error> {
     unsafe { gl::BindVertexArray(arr) };
     check_error()
 }
 
 #[inline]
-pub fn buffer_data(target: BufferTarget, size: gl::types::GLsizeiptr, data: *const c_void, usage: BufferUsage) -> Result<(), String> {
+pub fn buffer_data(target: BufferTarget, size: gl::types::GLsizeiptr, data: *const c_void, usage: BufferUsage) -> Result<(), Error> {
     unsafe { gl::BufferData(target as u32, size, data, usage as u32) };
     check_error()
 }
 
 #[inline]
-pub fn create_shader(shader_type: ShaderType) -> Result<u32, String> {
+pub fn create_shader(shader_type: ShaderType) -> Result<u32, Error> {
     let shader = unsafe { gl::CreateShader(shader_type as u32) };
     check_error()?;
     Ok(shader)
 }
 
 #[inline]
-pub fn delete_shader(shader: u32) -> Result<(), String> {
+pub fn delete_shader(shader: u32) -> Result<(), Error> {
     unsafe { gl::DeleteShader(shader) };
     check_error()
 }
 
 #[inline]
-pub fn attach_shader(program: u32, shader: u32) -> Result<(), String> {
+pub fn attach_shader(program: u32, shader: u32) -> Result<(), Error> {
     unsafe { gl::AttachShader(program, shader) };
     check_error()
 }
 
 #[inline]
-pub fn detach_shader(program: u32, shader: u32) -> Result<(), String> {
+pub fn detach_shader(program: u32, shader: u32) -> Result<(), Error> {
     unsafe { gl::DetachShader(program, shader) };
     check_error()
 }
 
 #[inline]
-pub fn shader_source(shader: u32, source: &str) -> Result<(), String> {
+pub fn shader_source(shader: u32, source: &str) -> Result<(), Error> {
     let sources: [*const i8; 1] = [source.as_ptr() as *const i8];
     let lengths: [i32; 1] = [1];
     unsafe { gl::ShaderSource(shader, 1, sources.as_ptr(), lengths.as_ptr()) };
@@ -774,13 +782,13 @@ pub fn shader_source(shader: u32, source: &str) -> Result<(), String> {
 }
 
 #[inline]
-pub fn compile_shader(shader: u32) -> Result<(), String> {
+pub fn compile_shader(shader: u32) -> Result<(), Error> {
     unsafe { gl::CompileShader(shader) };
     check_error()
 }
 
 #[inline]
-pub fn get_shader(shader: u32, param: ShaderParam) -> Result<i32, String> {
+pub fn get_shader(shader: u32, param: ShaderParam) -> Result<i32, Error> {
     let mut val: i32 = 0;
     unsafe { gl::GetShaderiv(shader, param as u32, &mut val as *mut i32) };
     check_error()?;
@@ -788,36 +796,35 @@ pub fn get_shader(shader: u32, param: ShaderParam) -> Result<i32, String> {
 }
 
 #[inline]
-pub fn get_shader_info_log(shader: u32) -> Result<String, String> {
+pub fn get_shader_info_log(shader: u32) -> Result<String, Error> {
     let mut len = get_shader(shader, ShaderParam::InfoLogLength)?;
     let mut bytes = vec![0; len as usize];
     unsafe { gl::GetShaderInfoLog(shader, len, &mut len as *mut i32, bytes.as_mut_ptr() as *mut i8) };
     check_error()?;
-    let log = String::from_utf8(bytes).map_err(|e| e.to_string())?;
-    Ok(log)
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
 }
 
 #[inline]
-pub fn create_program() -> Result<u32, String> {
+pub fn create_program() -> Result<u32, Error> {
     let program = unsafe { gl::CreateProgram() };
     check_error()?;
     Ok(program)
 }
 
 #[inline]
-pub fn delete_program(program: u32) -> Result<(), String> {
+pub fn delete_program(program: u32) -> Result<(), Error> {
     unsafe { gl::DeleteProgram(program) };
     check_error()
 }
 
 #[inline]
-pub fn link_program(program: u32) -> Result<(), String> {
+pub fn link_program(program: u32) -> Result<(), Error> {
     unsafe { gl::LinkProgram(program); }
     check_error()
 }
 
 #[inline]
-pub fn get_program(program: u32, param: ProgramParam) -> Result<i32, String> {
+pub fn get_program(program: u32, param: ProgramParam) -> Result<i32, Error> {
     let mut val: i32 = 0;
     unsafe { gl::GetProgramiv(program, param as u32, &mut val as *mut i32) };
     check_error()?;
@@ -825,13 +832,12 @@ pub fn get_program(program: u32, param: ProgramParam) -> Result<i32, String> {
 }
 
 #[inline]
-pub fn get_program_info_log(program: u32) -> Result<String, String> {
+pub fn get_program_info_log(program: u32) -> Result<String, Error> {
     let mut len = get_program(program, ProgramParam::InfoLogLength)?;
     let mut bytes = vec![0; len as usize];
     unsafe { gl::GetProgramInfoLog(program, len, &mut len as *mut i32, bytes.as_mut_ptr() as *mut i8) };
     check_error()?;
-    let log = String::from_utf8(bytes).map_err(|e| e.to_string())?;
-    Ok(log)
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
 }
 
 pub struct Uniform {
@@ -846,7 +852,7 @@ impl Uniform {
 }
 
 #[inline]
-pub fn get_active_uniform(program: u32, index: u32) -> Result<Uniform, String> {
+pub fn get_active_uniform(program: u32, index: u32) -> Result<Uniform, Error> {
     let max_name_len = get_program(program, ProgramParam::ActiveUniformMaxLength)?;
     let mut len: i32 = 0;
     let mut size: i32 = 0;
@@ -864,7 +870,7 @@ pub fn get_active_uniform(program: u32, index: u32) -> Result<Uniform, String> {
         )
     }
     check_error()?;
-    let name = String::from_utf8(name).map_err(|e| e.to_string())?;
+    let name = String::from_utf8_lossy(&name).into_owned();
     let ty = UniformType::from(ty)?;
     Ok(Uniform {
         size: size as usize,
@@ -874,134 +880,134 @@ pub fn get_active_uniform(program: u32, index: u32) -> Result<Uniform, String> {
 }
 
 #[inline]
-pub fn get_uniform_location(program: u32, name: &str) -> Result<i32, String> {
-    let cst = CString::new(name).unwrap();
+pub fn get_uniform_location(program: u32, name: &str) -> Result<i32, Error> {
+    let cst = CString::new(name).map_err(|_| Error::Other(format!("uniform name contains a NUL byte: {:?}", name)))?;
     let loc = unsafe { gl::GetUniformLocation(program, cst.as_ptr()) };
     check_error()?;
     Ok(loc)
 }
 
 #[inline]
-pub fn scissor(x: i32, y: i32, w: i32, h: i32) -> Result<(), String> {
+pub fn scissor(x: i32, y: i32, w: i32, h: i32) -> Result<(), Error> {
     unsafe { gl::Scissor(x, y, w, h) };
     check_error()
 }
 
 #[inline]
-pub fn viewport(x: i32, y: i32, w: i32, h: i32) -> Result<(), String> {
+pub fn viewport(x: i32, y: i32, w: i32, h: i32) -> Result<(), Error> {
     unsafe { gl::Viewport(x, y, w, h) };
     check_error()
 }
 
 #[inline]
-pub fn use_program(program: u32) -> Result<(), String> {
+pub fn use_program(program: u32) -> Result<(), Error> {
     unsafe { gl::UseProgram(program) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_1f(location: i32, v0: f32) -> Result<(), String> {
+pub fn uniform_1f(location: i32, v0: f32) -> Result<(), Error> {
     unsafe { gl::Uniform1f(location, v0) };
     check_error()
 }
 #[inline]
-pub fn uniform_1i(location: i32, v0: i32) -> Result<(), String> {
+pub fn uniform_1i(location: i32, v0: i32) -> Result<(), Error> {
     unsafe { gl::Uniform1i(location, v0) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_1iv(location: i32, v: &[i32]) -> Result<(), String> {
+pub fn uniform_1iv(location: i32, v: &[i32]) -> Result<(), Error> {
     unsafe { gl::Uniform1iv(location, v.len() as i32, v.as_ptr() as *const i32) };
     check_error()
 }
 #[inline]
-pub fn uniform_2f(location: i32, v0: f32, v1: f32) -> Result<(), String> {
+pub fn uniform_2f(location: i32, v0: f32, v1: f32) -> Result<(), Error> {
     unsafe { gl::Uniform2f(location, v0, v1) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_3f(location: i32, v0: f32, v1: f32, v2: f32) -> Result<(), String> {
+pub fn uniform_3f(location: i32, v0: f32, v1: f32, v2: f32) -> Result<(), Error> {
     unsafe { gl::Uniform3f(location, v0, v1, v2) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_4f(location: i32, v0: f32, v1: f32, v2: f32, v3: f32) -> Result<(), String> {
+pub fn uniform_4f(location: i32, v0: f32, v1: f32, v2: f32, v3: f32) -> Result<(), Error> {
     unsafe { gl::Uniform4f(location, v0, v1, v2, v3) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_1fv(location: i32, v: &[f32]) -> Result<(), String> {
+pub fn uniform_1fv(location: i32, v: &[f32]) -> Result<(), Error> {
     unsafe { gl::Uniform1fv(location, v.len() as i32, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_2fv(location: i32, v: &[[f32; 2]]) -> Result<(), String> {
+pub fn uniform_2fv(location: i32, v: &[[f32; 2]]) -> Result<(), Error> {
     unsafe { gl::Uniform2fv(location, v.len() as i32, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_3fv(location: i32, v: &[[f32; 3]]) -> Result<(), String> {
+pub fn uniform_3fv(location: i32, v: &[[f32; 3]]) -> Result<(), Error> {
     unsafe { gl::Uniform3fv(location, v.len() as i32, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_4fv(location: i32, v: &[[f32; 4]]) -> Result<(), String> {
+pub fn uniform_4fv(location: i32, v: &[[f32; 4]]) -> Result<(), Error> {
     unsafe { gl::Uniform4fv(location, v.len() as i32, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_matrix_2f(location: i32, transpose: bool, v: &[f32; 4]) -> Result<(), String> {
+pub fn uniform_matrix_2f(location: i32, transpose: bool, v: &[f32; 4]) -> Result<(), Error> {
     unsafe { gl::UniformMatrix2fv(location, 1, transpose as u8, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_matrix_3f(location: i32, transpose: bool, v: &[f32; 9]) -> Result<(), String> {
+pub fn uniform_matrix_3f(location: i32, transpose: bool, v: &[f32; 9]) -> Result<(), Error> {
     unsafe { gl::UniformMatrix3fv(location, 1, transpose as u8, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_matrix_4f(location: i32, transpose: bool, v: *const gl::types::GLfloat) -> Result<(), String> {
+pub fn uniform_matrix_4f(location: i32, transpose: bool, v: *const gl::types::GLfloat) -> Result<(), Error> {
     unsafe { gl::UniformMatrix4fv(location, 1, transpose as u8, v) };         
     check_error()
 }
 
 #[inline]
-pub fn uniform_matrix_2fv(location: i32, transpose: bool, v: &[[f32; 4]]) -> Result<(), String> {
+pub fn uniform_matrix_2fv(location: i32, transpose: bool, v: &[[f32; 4]]) -> Result<(), Error> {
     
     unsafe { gl::UniformMatrix2fv(location, v.len() as i32, transpose as u8, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_matrix_3fv(location: i32, transpose: bool, v: &[[f32; 9]]) -> Result<(), String> {
+pub fn uniform_matrix_3fv(location: i32, transpose: bool, v: &[[f32; 9]]) -> Result<(), Error> {
     unsafe { gl::UniformMatrix3fv(location, v.len() as i32, transpose as u8, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn uniform_matrix_4fv(location: i32, transpose: bool, v: &[[f32; 16]]) -> Result<(), String> {
+pub fn uniform_matrix_4fv(location: i32, transpose: bool, v: &[[f32; 16]]) -> Result<(), Error> {
     unsafe { gl::UniformMatrix4fv(location, v.len() as i32, transpose as u8, v.as_ptr() as *const f32) };
     check_error()
 }
 
 #[inline]
-pub fn draw_elements(mode: PrimitiveType, count: usize, index_type: IndexType) -> Result<(), String> {
+pub fn draw_elements(mode: PrimitiveType, count: usize, index_type: IndexType) -> Result<(), Error> {
     unsafe { gl::DrawElements(mode as u32, count as i32, index_type as u32, std::ptr::null()) };
     check_error()
 }
 
 #[inline]
-pub fn draw_arrays(mode: PrimitiveType, start: i32, count: usize) -> Result<(), String> {
+pub fn draw_arrays(mode: PrimitiveType, start: i32, count: usize) -> Result<(), Error> {
     unsafe { gl::DrawArrays(mode as u32, start, count as i32) };
     check_error()
 }
\ No newline at end of file
@@ -1,5 +1,96 @@
+use crate::graphics::{ batcher::DEFAULT_VERT_CODE, open_gl, Texture };
+use crate::Error;
 use gl;
-use std::{self, ffi::CStr, ffi::CString};
+use glam::{ Mat3, Mat4 };
+use std::{self, ffi::CStr, ffi::CString, rc::Rc};
+
+/// A value given to a Shader uniform, see `Batcher::set_uniform`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Uniform {
+    Int(i32),
+    Float(f32),
+    Vec2(f32, f32),
+    Vec3(f32, f32, f32),
+    Vec4(f32, f32, f32, f32),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+
+    /// A texture, kept alive until it is drawn, and the index of the extra texture unit it is bound to.
+    /// The first unit holds the texture being drawn, so the index 0 is bound to the unit 1.
+    Texture(Rc<Texture>, u32),
+}
+
+impl Uniform {
+    /// Creates a texture uniform bound to the given extra texture unit, starting at 0.
+    ///
+    pub fn texture(texture: &Rc<Texture>, unit: u32) -> Self {
+        Self::Texture(Rc::clone(texture), unit)
+    }
+
+    /// Gives the value to the uniform at the location, in the program in use.
+    ///
+    pub(crate) fn apply(&self, location: i32) -> Result<(), Error> {
+        match *self {
+            Self::Int(v)              => open_gl::uniform_1i(location, v),
+            Self::Float(v)            => open_gl::uniform_1f(location, v),
+            Self::Vec2(x, y)          => open_gl::uniform_2f(location, x, y),
+            Self::Vec3(x, y, z)       => open_gl::uniform_3f(location, x, y, z),
+            Self::Vec4(x, y, z, w)    => open_gl::uniform_4f(location, x, y, z, w),
+            Self::Mat3(matrix)        => open_gl::uniform_matrix_3f(location, false, &matrix),
+            Self::Mat4(matrix)        => open_gl::uniform_matrix_4f(location, false, &matrix[0]),
+            Self::Texture(ref texture, unit) => {
+                open_gl::active_texture(unit + 1)?;
+                open_gl::bind_texture(open_gl::TextureTarget::Texture2D, texture.handle)?;
+                open_gl::active_texture(0)?;
+
+                open_gl::uniform_1i(location, unit as i32 + 1)
+            },
+        }
+    }
+}
+
+impl From<i32> for Uniform {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f32> for Uniform {
+    fn from(value: f32) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<(f32, f32)> for Uniform {
+    fn from((x, y): (f32, f32)) -> Self {
+        Self::Vec2(x, y)
+    }
+}
+
+impl From<(f32, f32, f32)> for Uniform {
+    fn from((x, y, z): (f32, f32, f32)) -> Self {
+        Self::Vec3(x, y, z)
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Uniform {
+    fn from((x, y, z, w): (f32, f32, f32, f32)) -> Self {
+        Self::Vec4(x, y, z, w)
+    }
+}
+
+impl From<Mat3> for Uniform {
+    fn from(matrix: Mat3) -> Self {
+        Self::Mat3(matrix.to_cols_array())
+    }
+}
+
+impl From<Mat4> for Uniform {
+    fn from(matrix: Mat4) -> Self {
+        Self::Mat4(matrix.to_cols_array())
+    }
+}
 
 /// Estrutura que armazena e manipula valores relacionados aos Shaders.
 /// 
@@ -26,7 +117,7 @@ impl Shader {
 
     /// Cria um novo shader usando os códigos fornecidos pela função.
     /// 
-    pub fn new(vert_code: &CStr, frag_code: &CStr) -> Result<Shader, String> {
+    pub fn new(vert_code: &CStr, frag_code: &CStr) -> Result<Shader, Error> {
         let vert_id = Shader::from_vertex_source(vert_code)?;
         let frag_id = Shader::from_fragment_source(frag_code)?;
 
@@ -63,7 +154,7 @@ impl Shader {
                 );
             }
 
-            return Err(error.to_string_lossy().into_owned());
+            return Err(Error::ShaderLink(error.to_string_lossy().into_owned()));
         }
 
         unsafe {
@@ -80,6 +171,17 @@ impl Shader {
         })
     }
 
+    /// Creates a Shader for the Batcher from a fragment shader, using the vertex shader of the Batcher.
+    ///
+    /// The fragment shader receives `o_texcoord`, `o_color` and `o_type` and the `u_texture` uniform,
+    /// see the default shaders in `batcher.rs`.
+    ///
+    pub fn from_fragment(frag_code: &str) -> Result<Shader, Error> {
+        let frag_code = CString::new(frag_code).map_err(|e| Error::ShaderCompile(e.to_string()))?;
+
+        Shader::new(&CString::new(DEFAULT_VERT_CODE).unwrap(), &frag_code)
+    }
+
     /// Usa este shader.
     /// 
     pub fn use_program(&mut self) {
@@ -90,13 +192,13 @@ impl Shader {
 
     /// Cria um novo Vertex Shader.
     /// 
-    pub fn from_vertex_source(source: &CStr) -> Result<gl::types::GLuint, String> {
+    pub fn from_vertex_source(source: &CStr) -> Result<gl::types::GLuint, Error> {
         shader_from_source(source, gl::VERTEX_SHADER)
     }
 
     /// Cria um novo Fragment Shader.
     /// 
-    pub fn from_fragment_source(source: &CStr) -> Result<gl::types::GLuint, String> {
+    pub fn from_fragment_source(source: &CStr) -> Result<gl::types::GLuint, Error> {
         shader_from_source(source, gl::FRAGMENT_SHADER)
     }
 }
@@ -118,7 +220,7 @@ impl Drop for Shader {
 pub fn shader_from_source(
     source: &CStr, 
     kind: gl::types::GLenum
-) -> Result<gl::types::GLuint, String> {
+) -> Result<gl::types::GLuint, Error> {
     let id = unsafe { gl::CreateShader(kind) };
 
     // Define a fonte do shader e o compila.
@@ -154,7 +256,7 @@ pub fn shader_from_source(
         }
 
         // Finalmente, retorna a mensagem de erro.
-        return Err(error.to_string_lossy().into_owned());
+        return Err(Error::ShaderCompile(error.to_string_lossy().into_owned()));
     }
 
     Ok(id)
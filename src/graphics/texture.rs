@@ -1,7 +1,9 @@
 use crate::graphics::open_gl;
+use crate::Error;
 
 /// A 2D texture used for rendering.
 /// 
+#[derive(Debug, PartialEq)]
 pub struct Texture {
     width:  u32,
     height: u32,
@@ -22,31 +24,34 @@ impl Texture {
 
     /// Creates a new Texture.
     /// 
-    pub fn new() -> Self {
-        let handle = open_gl::gen_texture().unwrap();
+    pub fn new() -> Result<Self, Error> {
+        let handle = open_gl::gen_texture()?;
 
-        Self {
+        Ok(Self {
             width:  0,
             height: 0,
             handle,
-        }
+        })
     }
 
     /// Loads the image stored in the given path.
     /// 
-    pub fn from_path(self, location: &str) -> Result<Self, image::ImageError> {
+    pub fn from_path(self, location: &str) -> Result<Self, Error> {
         let raw_image = image::open(std::path::Path::new(location))?;
 
-        Ok(self.from_dynamic_image(raw_image))
+        self.from_dynamic_image(raw_image)
     }
 
     /// Loads the image from a DynamicImage.
-    pub fn from_dynamic_image(mut self, raw_image: image::DynamicImage) -> Self {     
+    pub fn from_dynamic_image(mut self, raw_image: image::DynamicImage) -> Result<Self, Error> {
         self.width  = raw_image.width();
         self.height = raw_image.height();
 
+        // The texture is uploaded as RGBA, whatever the format of the image.
+        let pixels = raw_image.to_rgba8();
+
         // Gen texture.
-        open_gl::bind_texture(open_gl::TextureTarget::Texture2D, self.handle).unwrap();
+        open_gl::bind_texture(open_gl::TextureTarget::Texture2D, self.handle)?;
         open_gl::tex_image_2d(
             open_gl::TextureTarget::Texture2D,
             0,
@@ -56,8 +61,8 @@ impl Texture {
             0,
             open_gl::PixelFormat::RGBA,
             open_gl::PixelType::UnsignedByte,
-            raw_image.as_bytes()
-        ).unwrap();
+            pixels.as_raw()
+        )?;
 
         unsafe {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
@@ -65,7 +70,7 @@ impl Texture {
             gl::GenerateMipmap(gl::TEXTURE_2D);
         }
 
-        self
+        Ok(self)
     }
 
     /// Returns the width of the Texture.
@@ -83,6 +88,7 @@ impl Texture {
 
 impl Drop for Texture {
     fn drop(&mut self) {
-        open_gl::delete_texture(self.handle).unwrap();
+        // Errors can't be reported from a destructor.
+        let _ = open_gl::delete_texture(self.handle);
     }
 }
\ No newline at end of file
@@ -3,11 +3,22 @@ extern crate gl;
 extern crate glam;
 extern crate image;
 
+pub mod error;
+pub use error::Error;
+
 pub mod graphics;
-pub use graphics::{ Shader, Batcher, Texture, Quad, Canvas };
+pub use graphics::{ Shader, Uniform, Batcher, BlendMode, Texture, Quad, Canvas, Camera2D };
 
 pub mod system;
-pub use system::{ App, Configuration, KeyCode, MouseButton };
+pub use system::{ App, AppConfig, Fullscreen, LoopMode, Configuration, KeyCode, MouseButton };
+pub use system::{ Input, PlayerGamepad, GamepadButton, GamepadAxis, SystemCursor, Deadzone, AxisSettings, Binding, AxisBinding, AxisSide, InputMap };
+pub use system::{ InputEvent, InputRecording };
+pub use system::{ Timer, ScheduleHandle, Clock, MonotonicClock, ManualClock, Lerp, Interpolated };
+pub use system::{ Scene, SceneAction, SceneManager, Transition, WipeDirection };
+pub use system::{ AppEvent, KeySym, Modifiers, KeyEvent, Composition, MouseButtonEvent, MouseMotionEvent, MouseWheelEvent, GamepadButtonEvent };
+
+pub mod tween;
+pub use tween::{ Ease, Tween, Sequence };
 
 #[cfg(test)]
 mod tests {
@@ -22,41 +33,47 @@ mod tests {
         /// Creates the Batcher at the Startup.
         /// This is called after the OpenGL Setup,
         ///
-        fn startup(&mut self, _app: &mut App) {
+        fn startup(&mut self, _app: &mut App) -> Result<(), Error> {
             println!("Hello there.");
 
-            self.batcher = Some(Batcher::new());
+            self.batcher = Some(Batcher::new()?);
+
+            Ok(())
         }
 
         /// Called when the game closes.
         /// 
-        fn shutdown(&mut self, _app: &mut App) {
+        fn shutdown(&mut self, _app: &mut App) -> Result<(), Error> {
             println!("Bye-bye");
+
+            Ok(())
         }
 
         /// Called every frame of the Application.
         ///
-        fn update(&mut self, _app: &mut App) {
+        fn update(&mut self, _app: &mut App) -> Result<(), Error> {
             self.angle += 0.1;
+
+            Ok(())
         }
 
         /// Draws a nice rectangle.
         ///
-        fn render(&mut self, app: &mut App) {
+        fn render(&mut self, app: &mut App) -> Result<(), Error> {
             let batcher = self.batcher.as_mut().expect("Where is my nice batcher?");
 
             // Creates a new canvas.
-            let canvas  = Canvas::new(16, 16);
+            let canvas  = Canvas::new(16, 16)?;
 
             // Resets the current drawing context.
-            batcher.origin(app);
+            batcher.origin(app)?;
 
             // Sets the current canvas and draws a rectangle.
-            batcher.set_canvas(&canvas);
+            batcher.set_canvas(&canvas)?;
             batcher.hollow_rectangle(0.0, 0.0, 16.0, 16.0, 2.0);
 
             // Sends the drawing data to the Canvas.
-            batcher.reset_canvas(app);
+            batcher.reset_canvas(app)?;
 
             // Draws the Canvas using the center as it origin point.
             batcher.canvas(
@@ -70,13 +87,13 @@ mod tests {
             );
                 
             // Finally, draw the screen.
-            batcher.present();
+            batcher.present()
         }
     }
 
     #[test]
     pub fn hello_world() {
-        App::new("Nice window!", 320, 240, 60.0).start(&mut HelloWorld {
+        AppConfig::new("Nice window!", 320, 240).headless(60).build().unwrap().start(&mut HelloWorld {
             angle:   0.0,
             batcher: None,
         }).unwrap();
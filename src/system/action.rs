@@ -0,0 +1,451 @@
+use super::input::{ Input, KeyCode, MouseButton, GamepadButton, GamepadAxis };
+use crate::Error;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Values with a `KeyCode`, converting a value in the gaps between them is undefined behavior.
+///
+pub(crate) const KEY_CODES: [RangeInclusive<i32>; 5] = [4..=129, 133..=164, 176..=221, 224..=231, 257..=290];
+
+pub(crate) const MOUSE_BUTTONS: [MouseButton; 6] = [
+    MouseButton::Left, MouseButton::Middle, MouseButton::Right, MouseButton::X1, MouseButton::X2,
+    MouseButton::Unknown,
+];
+
+pub(crate) const GAMEPAD_BUTTONS: [GamepadButton; 21] = [
+    GamepadButton::A, GamepadButton::B, GamepadButton::X, GamepadButton::Y,
+    GamepadButton::Back, GamepadButton::Guide, GamepadButton::Start,
+    GamepadButton::LeftStick, GamepadButton::RightStick, GamepadButton::LeftShoulder, GamepadButton::RightShoulder,
+    GamepadButton::DPadUp, GamepadButton::DPadDown, GamepadButton::DPadLeft, GamepadButton::DPadRight,
+    GamepadButton::Misc1, GamepadButton::Paddle1, GamepadButton::Paddle2, GamepadButton::Paddle3,
+    GamepadButton::Paddle4, GamepadButton::Touchpad,
+];
+
+pub(crate) const GAMEPAD_AXES: [GamepadAxis; 6] = [
+    GamepadAxis::LeftX, GamepadAxis::LeftY, GamepadAxis::RightX, GamepadAxis::RightY,
+    GamepadAxis::TriggerLeft, GamepadAxis::TriggerRight,
+];
+
+/// Side of an axis.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AxisSide {
+    Negative,
+    Positive,
+}
+
+impl AxisSide {
+    /// Whether the axis value is pushed to this side, at least as far as the threshold.
+    ///
+    pub fn is_pushed(self, value: f32, threshold: f32) -> bool {
+        match self {
+            Self::Negative => value <= -threshold,
+            Self::Positive => value >=  threshold,
+        }
+    }
+}
+
+/// A physical input that can trigger an action.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Button(GamepadButton),
+
+    /// A stick or trigger pushed to one side, used as a button.
+    Axis(GamepadAxis, AxisSide),
+}
+
+/// An input that moves a named axis.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AxisBinding {
+    /// Two buttons, the first one moves the axis to -1 and the second one to 1.
+    Buttons(Binding, Binding),
+
+    /// An analog stick or trigger.
+    Analog(GamepadAxis),
+}
+
+impl AxisBinding {
+    /// Returns the value of the binding, from -1 to 1.
+    ///
+    pub fn value(&self, input: &Input) -> f32 {
+        match *self {
+            Self::Buttons(negative, positive) => {
+                let negative = if input.binding_down(negative) { 1.0 } else { 0.0 };
+                let positive = if input.binding_down(positive) { 1.0 } else { 0.0 };
+
+                positive - negative
+            },
+            Self::Analog(axis) => input.get_axis(axis),
+        }
+    }
+}
+
+/// Named actions and axes bound to keys, mouse buttons and gamepads.
+///
+/// The Application input holds one in `Input::actions`, queried with `Input::action_down` and friends.
+///
+/// ```ignore
+/// app.input.actions.bind("jump", Binding::Key(KeyCode::Space));
+/// app.input.actions.bind("jump", Binding::Button(GamepadButton::A));
+/// app.input.actions.bind_axis("move_x", AxisBinding::Analog(GamepadAxis::LeftX));
+///
+/// if app.input.action_pressed("jump") { .. }
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputMap {
+    actions: HashMap<String, Vec<Binding>>,
+    axes:    HashMap<String, Vec<AxisBinding>>,
+}
+
+impl InputMap {
+    /// Creates an empty InputMap.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an input to the action.
+    ///
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let bindings = self.actions.entry(action.to_string()).or_default();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes an input from the action.
+    ///
+    pub fn unbind(&mut self, action: &str, binding: Binding) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|bound| *bound != binding);
+        }
+    }
+
+    /// Replaces every input of the action with a single one, used to rebind controls at runtime.
+    ///
+    pub fn rebind(&mut self, action: &str, binding: Binding) {
+        self.actions.insert(action.to_string(), vec![binding]);
+    }
+
+    /// Removes the action and its inputs.
+    ///
+    pub fn clear(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    /// Returns the inputs bound to the action.
+    ///
+    pub fn get_bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    /// Adds an input to the axis.
+    ///
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self.axes.entry(axis.to_string()).or_default();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes an input from the axis.
+    ///
+    pub fn unbind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        if let Some(bindings) = self.axes.get_mut(axis) {
+            bindings.retain(|bound| *bound != binding);
+        }
+    }
+
+    /// Replaces every input of the axis with a single one.
+    ///
+    pub fn rebind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        self.axes.insert(axis.to_string(), vec![binding]);
+    }
+
+    /// Removes the axis and its inputs.
+    ///
+    pub fn clear_axis(&mut self, axis: &str) {
+        self.axes.remove(axis);
+    }
+
+    /// Returns the inputs bound to the axis.
+    ///
+    pub fn get_axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    /// Writes the bindings as text, one action or axis per line.
+    /// Names that `load` can't read back, like ones with `=` or surrounding spaces, are an error.
+    ///
+    /// ```text
+    /// action jump = key:Space, button:A
+    /// axis move_x = key:Left/key:Right, analog:LeftX
+    /// ```
+    ///
+    pub fn save(&self) -> Result<String, Error> {
+        let mut text = String::new();
+
+        // Sorted, so saving the same bindings always gives the same file.
+        let mut actions: Vec<_> = self.actions.iter().collect();
+        let mut axes:    Vec<_> = self.axes.iter().collect();
+
+        actions.sort_by(|a, b| a.0.cmp(b.0));
+        axes.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, bindings) in actions {
+            check_name(name)?;
+
+            let bindings: Vec<String> = bindings.iter().map(|binding| binding.to_string()).collect();
+            text += &format!("action {} = {}\n", name, bindings.join(", "));
+        }
+
+        for (name, bindings) in axes {
+            check_name(name)?;
+
+            let bindings: Vec<String> = bindings.iter().map(|binding| binding.to_string()).collect();
+            text += &format!("axis {} = {}\n", name, bindings.join(", "));
+        }
+
+        Ok(text)
+    }
+
+    /// Reads bindings written by `save`, empty lines and lines starting with `#` are ignored.
+    ///
+    pub fn load(text: &str) -> Result<Self, Error> {
+        let mut map = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || Error::Other(format!("invalid input binding on line {}: {}", number + 1, line));
+
+            let (kind, rest)     = line.split_once(' ').ok_or_else(invalid)?;
+            let (name, bindings) = rest.split_once('=').ok_or_else(invalid)?;
+            let name             = name.trim();
+
+            if name.is_empty() {
+                return Err(invalid());
+            }
+
+            let bindings = bindings.split(',').map(str::trim).filter(|binding| !binding.is_empty());
+
+            match kind {
+                "action" => {
+                    map.actions.insert(name.to_string(), Vec::new());
+
+                    for binding in bindings {
+                        map.bind(name, binding.parse().map_err(|_| invalid())?);
+                    }
+                },
+                "axis" => {
+                    map.axes.insert(name.to_string(), Vec::new());
+
+                    for binding in bindings {
+                        map.bind_axis(name, binding.parse().map_err(|_| invalid())?);
+                    }
+                },
+
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Saves the bindings to a file.
+    ///
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.save()?)?;
+        Ok(())
+    }
+
+    /// Loads bindings from a file.
+    ///
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::load(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Key(key)                        => write!(f, "key:{:?}", key),
+            Self::Mouse(button)                   => write!(f, "mouse:{:?}", button),
+            Self::Button(button)                  => write!(f, "button:{:?}", button),
+            Self::Axis(axis, AxisSide::Negative)  => write!(f, "axis:-{:?}", axis),
+            Self::Axis(axis, AxisSide::Positive)  => write!(f, "axis:+{:?}", axis),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let invalid = || Error::Other(format!("invalid input binding: {}", text));
+
+        let (kind, name) = text.trim().split_once(':').ok_or_else(invalid)?;
+
+        // Names are the ones printed by `Debug`, so they don't need SDL to be parsed.
+        let binding = match kind {
+            "key" => KEY_CODES
+                .into_iter()
+                .flatten()
+                .filter_map(KeyCode::from_i32)
+                .find(|key| format!("{:?}", key) == name)
+                .map(Binding::Key),
+            "mouse" => MOUSE_BUTTONS
+                .into_iter()
+                .find(|button| format!("{:?}", button) == name)
+                .map(Binding::Mouse),
+            "button" => GAMEPAD_BUTTONS
+                .into_iter()
+                .find(|button| format!("{:?}", button) == name)
+                .map(Binding::Button),
+            "axis" => {
+                let (side, name) = match name.split_at_checked(1) {
+                    Some(("-", name)) => (AxisSide::Negative, name),
+                    Some(("+", name)) => (AxisSide::Positive, name),
+
+                    _ => return Err(invalid()),
+                };
+
+                parse_axis(name).map(|axis| Binding::Axis(axis, side))
+            },
+
+            _ => None,
+        };
+
+        binding.ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for AxisBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Buttons(negative, positive) => write!(f, "{}/{}", negative, positive),
+            Self::Analog(axis)                => write!(f, "analog:{:?}", axis),
+        }
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let text = text.trim();
+
+        if let Some((negative, positive)) = text.split_once('/') {
+            return Ok(Self::Buttons(negative.parse()?, positive.parse()?));
+        }
+
+        text.strip_prefix("analog:")
+            .and_then(parse_axis)
+            .map(Self::Analog)
+            .ok_or_else(|| Error::Other(format!("invalid axis binding: {}", text)))
+    }
+}
+
+/// Finds a gamepad axis by its `Debug` name.
+///
+/// Returns an error for action and axis names that wouldn't be read back the same by `InputMap::load`.
+///
+fn check_name(name: &str) -> Result<(), Error> {
+    if name.is_empty() || name != name.trim() || name.contains(['=', ',', '\n', '\r']) {
+        return Err(Error::Other(format!("the input name {:?} can't be saved", name)));
+    }
+
+    Ok(())
+}
+
+fn parse_axis(name: &str) -> Option<GamepadAxis> {
+    GAMEPAD_AXES.into_iter().find(|axis| format!("{:?}", axis) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_survive_a_round_trip() {
+        let mut map = InputMap::new();
+
+        map.bind("jump", Binding::Key(KeyCode::Space));
+        map.bind("jump", Binding::Button(GamepadButton::A));
+        map.bind("fire", Binding::Mouse(MouseButton::Left));
+        map.bind("fire", Binding::Axis(GamepadAxis::TriggerRight, AxisSide::Positive));
+        map.bind_axis("move_x", AxisBinding::Buttons(Binding::Key(KeyCode::Left), Binding::Key(KeyCode::Right)));
+        map.bind_axis("move_x", AxisBinding::Analog(GamepadAxis::LeftX));
+
+        let text = map.save().unwrap();
+
+        assert!(text.contains("action jump = key:Space, button:A\n"));
+        assert!(text.contains("axis move_x = key:Left/key:Right, analog:LeftX\n"));
+        assert_eq!(InputMap::load(&text).unwrap(), map);
+
+        map.rebind("jump", Binding::Key(KeyCode::W));
+        assert_eq!(map.get_bindings("jump"), [Binding::Key(KeyCode::W)]);
+
+        assert!(InputMap::load("action jump = key:NotAKey").is_err());
+        assert!(InputMap::load("jump = key:Space").is_err());
+    }
+
+    #[test]
+    fn names_that_cant_be_loaded_arent_saved() {
+        let mut map = InputMap::new();
+
+        map.bind("a=b", Binding::Key(KeyCode::Space));
+        assert!(map.save().is_err());
+
+        map.clear("a=b");
+        map.bind_axis(" move", AxisBinding::Analog(GamepadAxis::LeftX));
+        assert!(map.save().is_err());
+
+        map.clear_axis(" move");
+        map.bind("move up", Binding::Key(KeyCode::Up));
+        assert_eq!(InputMap::load(&map.save().unwrap()).unwrap(), map);
+    }
+
+    #[test]
+    fn actions_read_the_input() {
+        let mut input = Input::new();
+
+        input.actions.bind("jump", Binding::Key(KeyCode::Space));
+        input.actions.bind("jump", Binding::Key(KeyCode::Up));
+        input.actions.bind_axis("move_x", AxisBinding::Buttons(Binding::Key(KeyCode::A), Binding::Key(KeyCode::D)));
+
+        input.do_key_down(KeyCode::Space);
+        input.do_key_down(KeyCode::D);
+        assert!(input.action_pressed("jump"));
+        assert_eq!(input.action_axis("move_x"), 1.0);
+
+        // Pressing a second key of a held action is not a new press.
+        input.update();
+        input.do_key_down(KeyCode::Up);
+        assert!(input.action_down("jump"));
+        assert!(!input.action_pressed("jump"));
+
+        input.update();
+        input.do_key_up(KeyCode::Space);
+        assert!(!input.action_released("jump"));
+
+        input.update();
+        input.do_key_up(KeyCode::Up);
+        assert!(input.action_released("jump"));
+        assert!(!input.action_down("unknown"));
+    }
+}
@@ -1,141 +1,482 @@
 use crate::graphics::open_gl;
-use super::{ Timer, Input };
+use crate::Error;
+use super::{ Timer, Input, AppConfig, Fullscreen, LoopMode, ManualClock, SystemCursor, InputEvent, InputRecording };
+use super::event::*;
+use super::schedule::Scheduler;
 use sdl2;
 use sdl2::event::{ Event, WindowEvent };
+use std::path::Path;
+use std::time::{ Duration, Instant };
 
 /// Trait that implements main loop callbacks.
 ///
+/// Returning an error from any callback stops the main loop, calls `shutdown`
+/// and makes `App::start` return that error.
+///
 pub trait Configuration {
     /// Called when starting the Application.
     /// 
-    fn startup(&mut self, app: &mut App);
+    fn startup(&mut self, app: &mut App) -> Result<(), Error>;
 
-    /// Called when closing the Application.
+    /// Called when closing the Application, even if another callback failed.
     ///
-    fn shutdown(&mut self, app: &mut App);
+    fn shutdown(&mut self, app: &mut App) -> Result<(), Error>;
 
     /// Do logic here. Called in every frame of the Application.
     ///
-    fn update(&mut self, app: &mut App);
+    fn update(&mut self, app: &mut App) -> Result<(), Error>;
     
     /// Called in Application's Rendering state.
     ///
-    fn render(&mut self, app: &mut App);
+    fn render(&mut self, app: &mut App) -> Result<(), Error>;
+
+    /// Called after the window changes its size, with the new size in screen coordinates.
+    /// Use `App::get_drawable_size` to get the size in pixels.
+    ///
+    fn resized(&mut self, _app: &mut App, _width: u32, _height: u32) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the window gains or loses the keyboard focus.
+    ///
+    fn on_focus_changed(&mut self, _app: &mut App, _focused: bool) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a key is pressed, repeated or released.
+    ///
+    fn on_key(&mut self, _app: &mut App, _event: &KeyEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called with the UTF-8 text typed by the user.
+    ///
+    fn on_text_input(&mut self, _app: &mut App, _text: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the text being composed with an input method changes.
+    ///
+    fn on_text_editing(&mut self, _app: &mut App, _composition: &Composition) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a mouse button is pressed or released.
+    ///
+    fn on_mouse_button(&mut self, _app: &mut App, _event: &MouseButtonEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the mouse moves.
+    ///
+    fn on_mouse_motion(&mut self, _app: &mut App, _event: &MouseMotionEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the mouse wheel is scrolled.
+    ///
+    fn on_mouse_wheel(&mut self, _app: &mut App, _event: &MouseWheelEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a file is dropped on the window.
+    ///
+    fn on_file_dropped(&mut self, _app: &mut App, _path: &Path) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a gamepad is plugged in, with the player it was given.
+    ///
+    fn on_gamepad_connected(&mut self, _app: &mut App, _player: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a gamepad is unplugged, with the player that was using it.
+    ///
+    fn on_gamepad_disconnected(&mut self, _app: &mut App, _player: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when a gamepad button is pressed or released.
+    ///
+    fn on_gamepad_button(&mut self, _app: &mut App, _event: &GamepadButtonEvent) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 /// Battery Application.
 ///
 pub struct App {
-    sdl_context: sdl2::Sdl,
-    sdl_video:   sdl2::VideoSubsystem,
-    sdl_window:  sdl2::video::Window,
+    sdl_context:    sdl2::Sdl,
+    sdl_video:      sdl2::VideoSubsystem,
+    sdl_window:     sdl2::video::Window,
+    sdl_controller: sdl2::GameControllerSubsystem,
 
     running:             bool,
     focused:             bool,
+    minimized:           bool,
     run_while_unfocused: bool,
 
+    headless:       bool,
+    headless_clock: Option<ManualClock>,
+    frame_limit:    Option<u32>,
+    frames_drawn:   u32,
+    vsync:          bool,
+    loop_mode:      LoopMode,
+    context:        Option<sdl2::video::GLContext>,
+    cursor:         Option<sdl2::mouse::Cursor>,
+
     title:  String,
     width:  u32,
     height: u32,
 
+    drawable_width:  u32,
+    drawable_height: u32,
+
+    events: Vec<AppEvent>,
+
+    recording:     Option<InputRecording>,
+    pending_input: Vec<InputEvent>,
+    replay:        Option<std::vec::IntoIter<Vec<InputEvent>>>,
+    idle_gamepads: Vec<(sdl2::controller::GameController, String)>,
+
     pub input: Input,
     pub timer: Timer,
 }
 
 impl App {
-    /// Creates a new Application.
+    /// Creates a new Application from the given settings.
     ///
-    pub fn new(title: &str, width: u32, height: u32, framerate: f64) -> Self {
-        let sdl_context = sdl2::init().unwrap();
-        let sdl_video   = sdl_context.video().unwrap();
-        let sdl_window  = sdl_video.window(title, width, height)
-            .opengl()
-            .build()
-            .unwrap();
+    pub fn new(config: AppConfig) -> Result<Self, Error> {
+        let headless = config.headless.is_some();
 
-        // OpenGL Attributes.
+        // The offscreen driver provides an EGL context without a display.
+        // It does not override a driver chosen by the SDL_VIDEODRIVER environment variable.
+        if headless {
+            sdl2::hint::set("SDL_VIDEODRIVER", "offscreen");
+        }
+
+        let sdl_context = sdl2::init().map_err(Error::Sdl)?;
+        let sdl_video   = sdl_context.video().map_err(Error::Sdl)?;
+
+        // OpenGL Attributes, they must be set before the window is created.
         let gl_attrib = sdl_video.gl_attr();
 
         gl_attrib.set_context_profile(sdl2::video::GLProfile::Core);
-        gl_attrib.set_context_version(3, 3);
+        gl_attrib.set_context_version(config.gl_version.0, config.gl_version.1);
+        gl_attrib.set_double_buffer(true);
+
+        if config.msaa_samples > 0 {
+            gl_attrib.set_multisample_buffers(1);
+            gl_attrib.set_multisample_samples(config.msaa_samples);
+        }
+
+        // Window settings.
+        let mut builder = sdl_video.window(&config.title, config.width, config.height);
+
+        builder.opengl();
+
+        match config.fullscreen {
+            Fullscreen::Off       => {},
+            Fullscreen::Desktop   => { builder.fullscreen_desktop(); },
+            Fullscreen::Exclusive => { builder.fullscreen(); },
+        }
+
+        if let Some((x, y)) = config.position {
+            builder.position(x, y);
+        }
+
+        if config.resizable {
+            builder.resizable();
+        }
+
+        if config.borderless {
+            builder.borderless();
+        }
+
+        if config.high_dpi {
+            builder.allow_highdpi();
+        }
+
+        if headless {
+            builder.hidden();
+        }
+
+        let sdl_window = builder.build().map_err(|e| Error::Sdl(e.to_string()))?;
+
+        // Fullscreen and high-DPI windows may not have the requested size.
+        let (width, height)                   = sdl_window.size();
+        let (drawable_width, drawable_height) = sdl_window.drawable_size();
+
+        // Gamepads are opened as they are plugged in, see `add_gamepad`.
+        let sdl_controller = sdl_context.game_controller().map_err(Error::Sdl)?;
+
+        // Headless Applications use a clock that advances one frame per iteration, and the Timer runs exactly one frame per update.
+        let headless_clock = if headless { Some(ManualClock::new()) } else { None };
+
+        let mut timer = match &headless_clock {
+            Some(clock) => Timer::with_clock(config.frame_rate, clock.clone()),
+            None        => Timer::new(config.frame_rate),
+        };
+
+        timer.fixed_step = matches!(config.loop_mode, LoopMode::Fixed { .. });
+        timer.lockstep   = headless;
 
         // Return the SDL Platform.
-        Self {
+        Ok(Self {
             sdl_context,
             sdl_video,
             sdl_window,
+            sdl_controller,
 
             running:             false,
             focused:             true,
+            minimized:           false,
             run_while_unfocused: false,
 
-            title: String::from(title),
+            headless,
+            headless_clock,
+            frame_limit:    config.headless,
+            frames_drawn:   0,
+            vsync:          config.vsync,
+            loop_mode:      config.loop_mode,
+            context:        None,
+            cursor:         None,
+
+            title: config.title,
             width,
             height,
 
+            drawable_width,
+            drawable_height,
+
+            events: Vec::new(),
+
+            recording:     None,
+            pending_input: Vec::new(),
+            replay:        None,
+            idle_gamepads: Vec::new(),
+
             input: Input::new(),
-            timer: Timer::new(framerate),
-        }
+            timer,
+        })
     }
 
     /// Starts running the Application.
     ///
-    pub fn start(mut self, config: &mut impl Configuration) -> Result<(), String> {
+    pub fn start(mut self, config: &mut impl Configuration) -> Result<(), Error> {
         if self.running {
-            return Err("App already running".to_string());
+            return Err(Error::Other("App already running".to_string()));
         }
 
         // Load OpenGL Function pointers.
-        let _context = self.sdl_window.gl_create_context().unwrap();
-        let _gl      = open_gl::load(|s| self.sdl_video.gl_get_proc_address(s) as *const std::os::raw::c_void);
+        self.context = Some(self.sdl_window.gl_create_context().map_err(Error::Sdl)?);
+        open_gl::load(|s| self.sdl_video.gl_get_proc_address(s) as *const std::os::raw::c_void);
+
+        self.apply_swap_interval()?;
 
         // Starting up the Application.
-        self.running = true;
-        self.focused = true;
-        config.startup(&mut self);
+        self.running      = true;
+        self.focused      = true;
+        self.frames_drawn = 0;
+        self.update_size();
+
+        let result = self.run(config);
+
+        // Closing the Application, the first error is the one reported.
+        self.close();
+
+        let shutdown = config.shutdown(&mut self);
+
+        self.context = None;
+
+        result.and(shutdown)
+    }
+
+    /// Calls the startup and runs the main loop until the Application closes or a callback fails.
+    ///
+    fn run(&mut self, config: &mut impl Configuration) -> Result<(), Error> {
+        config.startup(self)?;
 
         // Main loop.
-        let mut events = self.sdl_context.event_pump().unwrap();
+        let mut events = self.sdl_context.event_pump().map_err(Error::Sdl)?;
 
         while self.running {
+            let frame_start = Instant::now();
+
             // Process Sdl Events.
             for event in events.poll_iter() {
                 self.poll_event(event);
             }
 
-            if self.focused || self.run_while_unfocused {
-                self.timer.update();
+            // Deliver the events to the Configuration hooks.
+            for event in std::mem::take(&mut self.events) {
+                self.dispatch_event(config, &event)?;
+            }
 
-                // Updates the Application when our accumulator exceeds the frame duration.
-                while self.timer.frame_accumulator >= 1.0 / self.timer.frame_rate {
-                    self.timer.frame_counter     += 1;
-                    self.timer.frame_accumulator -= 1.0 / self.timer.frame_rate;
+            let mut rendered = false;
 
-                    config.update(&mut self);
-                    
-                    self.input.update();
+            if self.focused || self.run_while_unfocused || self.headless {
+                if let Some(clock) = &self.headless_clock {
+                    clock.advance_secs(1.0 / self.timer.frame_rate);
                 }
 
-                // Renders the Application.
-                if self.running {
-                    open_gl::clear(open_gl::ClearMode::Color).unwrap();
-                    open_gl::clear_color(0.0, 0.0, 0.0, 1.0).unwrap();
+                self.timer.update();
+
+                match self.loop_mode {
+                    LoopMode::Fixed { max_steps } => {
+                        // Updates the Application once for each frame duration in our accumulator.
+                        for _ in 0..self.timer.take_steps(max_steps) {
+                            self.tick(config)?;
+                        }
+                    },
+                    _ => self.tick(config)?,
+                }
 
-                    config.render(&mut self);
+                // Renders the Application, there is nothing to show while minimized.
+                if self.running && !self.minimized {
+                    open_gl::clear(open_gl::ClearMode::Color)?;
+                    open_gl::clear_color(0.0, 0.0, 0.0, 1.0)?;
+
+                    config.render(self)?;
                     self.sdl_window.gl_swap_window();
+
+                    self.frames_drawn += 1;
+                    rendered           = true;
                 }
+
+                // Headless Applications stop after the requested number of frames.
+                if let Some(limit) = self.frame_limit {
+                    if self.frames_drawn >= limit {
+                        self.close();
+                    }
+                }
+            } else {
+                // The time spent unfocused is not a stall, it must not be caught up when the focus comes back.
+                self.timer.resync();
             }
 
-            // It avoids the high CPU consumption.
-            std::thread::sleep(std::time::Duration::new(0, 10000000));
+            // Sleeps the remaining frame budget, idle frames are always paced to avoid a busy loop.
+            if !self.headless && self.running && (!rendered || self.is_paced()) {
+                Self::pace(frame_start, 1.0 / self.timer.frame_rate);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs a single update of the Application.
+    ///
+    fn tick(&mut self, config: &mut impl Configuration) -> Result<(), Error> {
+        self.timer.frame_counter += 1;
+
+        // The recorded input is given at the start of the update, like the polled one.
+        let mut replayed = false;
+
+        if let Some(ticks) = &mut self.replay {
+            for event in ticks.next().unwrap_or_default() {
+                event.apply(&mut self.input);
+            }
+
+            replayed = ticks.len() == 0;
+        }
+
+        if let Some(recording) = &mut self.recording {
+            recording.push_tick(std::mem::take(&mut self.pending_input));
         }
 
-        // Closing the Application.
-        config.shutdown(&mut self);
+        config.update(self)?;
+
+        Scheduler::run(self, |app| &mut app.timer.scheduler, self.timer.get_update_delta())?;
+
+        self.input.update();
+
+        if replayed {
+            self.stop_replay();
+
+            // Headless Applications close at the end of the replay, so recorded sessions can run as tests.
+            if self.headless {
+                self.close();
+            }
+        }
 
         Ok(())
     }
 
+    /// Whether the loop sleeps to keep the frame rate, instead of relying on vsync or running uncapped.
+    ///
+    fn is_paced(&self) -> bool {
+        match self.loop_mode {
+            LoopMode::Fixed { .. } | LoopMode::Variable => !self.vsync,
+            LoopMode::VSync        | LoopMode::Uncapped => false,
+        }
+    }
+
+    /// Waits until the frame that started at `frame_start` has lasted `budget` seconds.
+    /// Sleeps most of the time and yields the last millisecond, since sleeping is not precise.
+    ///
+    fn pace(frame_start: Instant, budget: f64) {
+        let deadline = frame_start + Duration::from_secs_f64(budget);
+        let margin   = Duration::from_millis(1);
+
+        let now = Instant::now();
+
+        if now + margin < deadline {
+            std::thread::sleep(deadline - now - margin);
+        }
+
+        while Instant::now() < deadline {
+            std::thread::yield_now();
+        }
+    }
+
+    /// Sets how the main loop updates and paces the Application.
+    ///
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) -> Result<(), Error> {
+        self.loop_mode        = loop_mode;
+        self.timer.fixed_step = matches!(loop_mode, LoopMode::Fixed { .. });
+
+        if !self.timer.fixed_step {
+            self.timer.frame_accumulator = 0.0;
+        }
+
+        self.apply_swap_interval()
+    }
+
+    /// Returns how the main loop updates and paces the Application.
+    ///
+    pub fn get_loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
+    /// Sets whether buffer swaps wait for the vertical retrace.
+    ///
+    pub fn set_vsync(&mut self, vsync: bool) -> Result<(), Error> {
+        self.vsync = vsync;
+
+        self.apply_swap_interval()
+    }
+
+    /// Updates the swap interval of the OpenGL context for the current vsync and loop mode.
+    /// Does nothing before the context is created.
+    ///
+    fn apply_swap_interval(&self) -> Result<(), Error> {
+        if self.context.is_none() || self.headless {
+            return Ok(());
+        }
+
+        let vsync = match self.loop_mode {
+            LoopMode::VSync    => true,
+            LoopMode::Uncapped => false,
+            _                  => self.vsync,
+        };
+
+        self.sdl_video.gl_set_swap_interval(match vsync {
+            true  => sdl2::video::SwapInterval::VSync,
+            false => sdl2::video::SwapInterval::Immediate,
+        }).map_err(Error::Sdl)
+    }
+
     /// Closes the Window.
     ///
     pub fn close(&mut self) {
@@ -146,64 +487,445 @@ impl App {
     /// Process Window events.
     ///
     pub fn poll_event(&mut self, event: Event) {
+        // The replay is the only input while it runs, plugged gamepads are still opened and closed.
+        if self.replay.is_some() && is_input(&event) {
+            return;
+        }
+
         match event {
             Event::Window { timestamp: _, window_id: _, win_event} => {
                 match win_event {
                     WindowEvent::Close       { .. } => self.close(),
-                    WindowEvent::FocusGained { .. } => self.focused = true,
-                    WindowEvent::FocusLost   { .. } => self.focused = false,
+                    WindowEvent::FocusGained { .. } => {
+                        self.focused = true;
+                        self.events.push(AppEvent::FocusChanged(true));
+                    },
+                    WindowEvent::FocusLost   { .. } => {
+                        self.focused = false;
+                        self.events.push(AppEvent::FocusChanged(false));
+                    },
+                    WindowEvent::Minimized          => self.minimized = true,
+                    WindowEvent::Restored           => self.minimized = false,
+                    WindowEvent::Maximized          => self.minimized = false,
+
+                    // The drawable size may change without the window size when moving to another display.
+                    WindowEvent::Resized(..)        |
+                    WindowEvent::SizeChanged(..)    |
+                    WindowEvent::DisplayChanged(..) => self.queue_resize(),
 
                     _ => {},
                 }
             },
             Event::Quit { .. } => self.close(),
-            Event::KeyDown { timestamp: _, window_id: _, keycode: _, scancode, keymod: _, repeat: _ } => {
-                match scancode {
-                    Some(code) => self.input.do_key_down(code),
-                    None => {},
-                }
+            Event::KeyDown { timestamp: _, window_id: _, keycode, scancode: Some(code), keymod, repeat } => {
+                let event = KeyEvent {
+                    key:       code,
+                    symbol:    keycode,
+                    modifiers: Modifiers::from_sdl(keymod),
+                    pressed:   true,
+                    repeat,
+                };
+
+                self.feed_input(InputEvent::Key(event));
+                self.events.push(AppEvent::Key(event));
             },
-            Event::KeyUp { timestamp: _, window_id: _, keycode: _, scancode, keymod: _, repeat: _ } => {
-                match scancode {
-                    Some(code) => self.input.do_key_up(code),
-                    None => {},
-                }
+            Event::KeyUp { timestamp: _, window_id: _, keycode, scancode: Some(code), keymod, repeat } => {
+                let event = KeyEvent {
+                    key:       code,
+                    symbol:    keycode,
+                    modifiers: Modifiers::from_sdl(keymod),
+                    pressed:   false,
+                    repeat,
+                };
+
+                self.feed_input(InputEvent::Key(event));
+                self.events.push(AppEvent::Key(event));
+            },
+            Event::TextInput { timestamp: _, window_id: _, text } => {
+                self.feed_input(InputEvent::Text(text.clone()));
+                self.events.push(AppEvent::TextInput(text));
             },
-            Event::MouseButtonDown { timestamp: _, window_id: _, which: _, mouse_btn, clicks: _, x: _, y: _ } => {
-                self.input.do_mouse_down(mouse_btn);
+            Event::TextEditing { timestamp: _, window_id: _, text, start, length } => {
+                let composition = Composition {
+                    text,
+                    cursor:    start.max(0) as usize,
+                    selection: length.max(0) as usize,
+                };
+
+                self.feed_input(InputEvent::TextEditing(composition.clone()));
+                self.events.push(AppEvent::TextEditing(composition));
             },
-            Event::MouseButtonUp { timestamp: _, window_id: _, which: _, mouse_btn, clicks: _, x: _, y: _ } => {
-                self.input.do_mouse_up(mouse_btn);
+            Event::MouseButtonDown { timestamp: _, window_id: _, which: _, mouse_btn, clicks, x, y } => {
+                self.feed_input(InputEvent::MouseDown(mouse_btn));
+                self.events.push(AppEvent::MouseButton(MouseButtonEvent {
+                    button:   mouse_btn,
+                    pressed:  true,
+                    clicks,
+                    position: (x as f32, y as f32),
+                }));
             },
-            Event::MouseMotion { timestamp: _, window_id: _, which: _, mousestate: _, x, y, xrel: _, yrel: _ } => {
-                self.input.do_mouse_move((x as f32, y as f32));
+            Event::MouseButtonUp { timestamp: _, window_id: _, which: _, mouse_btn, clicks, x, y } => {
+                self.feed_input(InputEvent::MouseUp(mouse_btn));
+                self.events.push(AppEvent::MouseButton(MouseButtonEvent {
+                    button:   mouse_btn,
+                    pressed:  false,
+                    clicks,
+                    position: (x as f32, y as f32),
+                }));
             },
-            Event::ControllerDeviceAdded { timestamp: _, which } => {
-                self.input.do_gamepad_added(which);
+            Event::MouseMotion { timestamp: _, window_id: _, which: _, mousestate: _, x, y, xrel, yrel } => {
+                self.feed_input(InputEvent::MouseMotion {
+                    position: (x as f32, y as f32),
+                    delta:    (xrel as f32, yrel as f32),
+                });
+                self.events.push(AppEvent::MouseMotion(MouseMotionEvent {
+                    position: (x as f32, y as f32),
+                    delta:    (xrel as f32, yrel as f32),
+                }));
             },
+            Event::MouseWheel { direction, precise_x, precise_y, .. } => {
+                let flipped = direction == sdl2::mouse::MouseWheelDirection::Flipped;
+                let sign    = if flipped { -1.0 } else { 1.0 };
+
+                self.feed_input(InputEvent::MouseWheel((precise_x * sign, precise_y * sign)));
+                self.events.push(AppEvent::MouseWheel(MouseWheelEvent {
+                    delta: (precise_x * sign, precise_y * sign),
+                    flipped,
+                }));
+            },
+            Event::DropFile { timestamp: _, window_id: _, filename } => {
+                self.events.push(AppEvent::FileDropped(filename.into()));
+            },
+            Event::ControllerDeviceAdded { timestamp: _, which } => self.add_gamepad(which),
             Event::ControllerDeviceRemoved { timestamp: _, which } => {
-                self.input.do_gamepad_removed(which);
+                // Dropping the controller closes it.
+                if self.replay.is_some() {
+                    self.idle_gamepads.retain(|(controller, _)| controller.instance_id() != which);
+                } else if let Some(player) = self.input.get_player(which) {
+                    self.feed_input(InputEvent::GamepadRemoved(which));
+                    self.events.push(AppEvent::GamepadDisconnected(player));
+                }
             },
             Event::ControllerButtonDown { timestamp: _, which, button } => {
-                self.input.do_gamepad_down(which, button);
+                self.feed_input(InputEvent::GamepadDown(which, button));
+
+                if let Some(player) = self.input.get_player(which) {
+                    self.events.push(AppEvent::GamepadButton(GamepadButtonEvent { id: which, player, button, pressed: true }));
+                }
             },
             Event::ControllerButtonUp { timestamp: _, which, button } => {
-                self.input.do_gamepad_up(which, button);
+                self.feed_input(InputEvent::GamepadUp(which, button));
+
+                if let Some(player) = self.input.get_player(which) {
+                    self.events.push(AppEvent::GamepadButton(GamepadButtonEvent { id: which, player, button, pressed: false }));
+                }
+            },
+            Event::ControllerAxisMotion { timestamp: _, which, axis, value } => {
+                // The negative side goes one step further than the positive one.
+                self.feed_input(InputEvent::GamepadAxis(which, axis, (value as f32 / i16::MAX as f32).max(-1.0)));
             },
 
             _ => {},
         }
     }
 
+    /// Opens a gamepad that has just been plugged in and gives it a player slot.
+    /// The event carries the device index, the other gamepad events use the instance id.
+    ///
+    fn add_gamepad(&mut self, index: u32) {
+        // Devices that can't be opened are ignored, like the ones used by another program.
+        let Ok(controller) = self.sdl_controller.open(index) else {
+            return;
+        };
+
+        let id = controller.instance_id();
+
+        // Added events may be repeated for gamepads already open, the gamepads of a replay are not real.
+        let open = match self.replay {
+            Some(_) => self.idle_gamepads.iter().any(|(controller, _)| controller.instance_id() == id),
+            None    => self.input.get_player(id).is_some(),
+        };
+
+        if open {
+            return;
+        }
+
+        let guid = self.sdl_context
+            .joystick()
+            .and_then(|joystick| joystick.device_guid(index).map_err(|e| e.to_string()))
+            .map(|guid| guid.string())
+            .unwrap_or_default();
+
+        // The replay owns the Input, the gamepad joins it when the replay stops.
+        if self.replay.is_some() {
+            self.idle_gamepads.push((controller, guid));
+            return;
+        }
+
+        self.connect_gamepad(controller, guid);
+    }
+
+    /// Gives an open gamepad a player slot in the Input.
+    ///
+    fn connect_gamepad(&mut self, mut controller: sdl2::controller::GameController, guid: String) {
+        let id = controller.instance_id();
+
+        self.feed_input(InputEvent::GamepadAdded { id, name: controller.name(), guid });
+
+        let Some(player) = self.input.get_player(id) else {
+            return;
+        };
+
+        // Lights the player number on gamepads that can show it.
+        let _ = controller.set_player_index(Some(player as u32));
+
+        self.input.attach_controller(player, controller);
+        self.events.push(AppEvent::GamepadConnected(player));
+    }
+
+    /// Applies polled input to the Input, keeping it for the recording.
+    ///
+    fn feed_input(&mut self, event: InputEvent) {
+        event.apply(&mut self.input);
+
+        if self.recording.is_some() {
+            self.pending_input.push(event);
+        }
+    }
+
+    /// Starts recording the input received by each update, replacing the current recording.
+    ///
+    /// Replays start from a clean Input, with nothing held and no gamepad connected,
+    /// so start recording from the same state, like in `startup`, or the replay will diverge.
+    ///
+    pub fn start_recording(&mut self) {
+        self.recording = Some(InputRecording::new(self.timer.frame_rate));
+        self.pending_input.clear();
+    }
+
+    /// Stops recording and returns the recording.
+    ///
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.pending_input.clear();
+        self.recording.take()
+    }
+
+    /// Returns whether the input is being recorded.
+    ///
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Replays a recording from the next update, the polled input is ignored until it ends.
+    ///
+    /// The replay needs the fixed loop mode and the frame rate of the recording, so each update
+    /// covers the same time as when it was recorded.
+    ///
+    /// The Input is reset when the replay starts and when it stops, nothing stays held.
+    /// The gamepads are disconnected during the replay and connected again when it stops.
+    ///
+    pub fn replay(&mut self, recording: InputRecording) -> Result<(), Error> {
+        if !matches!(self.loop_mode, LoopMode::Fixed { .. }) {
+            return Err(Error::Other("replays need the fixed loop mode".to_string()));
+        }
+
+        if recording.get_frame_rate() != self.timer.frame_rate {
+            return Err(Error::Other(format!(
+                "the recording runs at {} updates per second, not {}",
+                recording.get_frame_rate(),
+                self.timer.frame_rate,
+            )));
+        }
+
+        for (player, controller, guid) in self.input.reset() {
+            self.idle_gamepads.push((controller, guid));
+            self.events.push(AppEvent::GamepadDisconnected(player));
+        }
+
+        self.replay = Some(recording.into_ticks());
+
+        Ok(())
+    }
+
+    /// Stops the replay and gives the input back to the user.
+    ///
+    pub fn stop_replay(&mut self) {
+        if self.replay.take().is_none() {
+            return;
+        }
+
+        // Drops the input and the gamepads of the replay, the plugged gamepads come back.
+        self.input.reset();
+
+        for (controller, guid) in std::mem::take(&mut self.idle_gamepads) {
+            self.connect_gamepad(controller, guid);
+        }
+    }
+
+    /// Returns whether a recording is being replayed.
+    ///
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Calls the Configuration hook matching the event.
+    ///
+    fn dispatch_event(&mut self, config: &mut impl Configuration, event: &AppEvent) -> Result<(), Error> {
+        match event {
+            AppEvent::Resized(width, height)      => config.resized(self, *width, *height),
+            AppEvent::FocusChanged(focused)       => config.on_focus_changed(self, *focused),
+            AppEvent::Key(event)                  => config.on_key(self, event),
+            AppEvent::TextInput(text)             => config.on_text_input(self, text),
+            AppEvent::TextEditing(composition)    => config.on_text_editing(self, composition),
+            AppEvent::MouseButton(event)          => config.on_mouse_button(self, event),
+            AppEvent::MouseMotion(event)          => config.on_mouse_motion(self, event),
+            AppEvent::MouseWheel(event)           => config.on_mouse_wheel(self, event),
+            AppEvent::FileDropped(path)           => config.on_file_dropped(self, path),
+            AppEvent::GamepadConnected(player)    => config.on_gamepad_connected(self, *player),
+            AppEvent::GamepadDisconnected(player) => config.on_gamepad_disconnected(self, *player),
+            AppEvent::GamepadButton(event)        => config.on_gamepad_button(self, event),
+        }
+    }
+
+    /// Starts sending text input and input method events, may show an on-screen keyboard.
+    ///
+    pub fn start_text_input(&mut self) {
+        self.sdl_video.text_input().start();
+    }
+
+    /// Stops sending text input events, use it when no text field is focused.
+    ///
+    pub fn stop_text_input(&mut self) {
+        self.sdl_video.text_input().stop();
+    }
+
+    /// Returns whether text input events are being sent.
+    ///
+    pub fn is_text_input_active(&self) -> bool {
+        self.sdl_video.text_input().is_active()
+    }
+
+    /// Sets the area of the text field in screen coordinates, the input method shows its candidates next to it.
+    ///
+    pub fn set_text_input_rect(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        self.sdl_video.text_input().set_rect(sdl2::rect::Rect::new(x, y, width, height));
+    }
+
+    /// Hides the cursor and reports only the motion of the mouse, used to move cameras.
+    /// See `Input::get_mouse_delta`.
+    ///
+    pub fn set_relative_mouse(&mut self, relative: bool) {
+        self.sdl_context.mouse().set_relative_mouse_mode(relative);
+    }
+
+    /// Returns whether the relative mouse mode is on.
+    ///
+    pub fn is_relative_mouse(&self) -> bool {
+        self.sdl_context.mouse().relative_mouse_mode()
+    }
+
+    /// Shows or hides the cursor over the window.
+    ///
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.sdl_context.mouse().show_cursor(visible);
+    }
+
+    /// Returns whether the cursor is shown.
+    ///
+    pub fn is_cursor_visible(&self) -> bool {
+        self.sdl_context.mouse().is_cursor_showing()
+    }
+
+    /// Moves the cursor to a position of the window.
+    ///
+    pub fn set_mouse_position(&mut self, x: i32, y: i32) {
+        self.sdl_context.mouse().warp_mouse_in_window(&self.sdl_window, x, y);
+    }
+
+    /// Uses one of the cursors of the system, like the text beam or the hand.
+    ///
+    pub fn set_system_cursor(&mut self, cursor: SystemCursor) -> Result<(), Error> {
+        let cursor = sdl2::mouse::Cursor::from_system(cursor).map_err(Error::Sdl)?;
+
+        cursor.set();
+        self.cursor = Some(cursor);
+
+        Ok(())
+    }
+
+    /// Uses an image as the cursor, the hot spot is the pixel that clicks.
+    /// Accepts the same images as `Texture::from_dynamic_image`.
+    ///
+    pub fn set_cursor_image(&mut self, image: &image::DynamicImage, hot_x: i32, hot_y: i32) -> Result<(), Error> {
+        let mut pixels      = image.to_rgba8();
+        let (width, height) = pixels.dimensions();
+
+        let surface = sdl2::surface::Surface::from_data(
+            &mut pixels,
+            width,
+            height,
+            width * 4,
+            sdl2::pixels::PixelFormatEnum::RGBA32,
+        ).map_err(Error::Sdl)?;
+
+        let cursor = sdl2::mouse::Cursor::from_surface(surface, hot_x, hot_y).map_err(Error::Sdl)?;
+
+        cursor.set();
+        self.cursor = Some(cursor);
+
+        Ok(())
+    }
+
+    /// Loads an image file and uses it as the cursor.
+    ///
+    pub fn set_cursor_from_path(&mut self, path: &str, hot_x: i32, hot_y: i32) -> Result<(), Error> {
+        self.set_cursor_image(&image::open(path)?, hot_x, hot_y)
+    }
+
+    /// Goes back to the default arrow cursor.
+    ///
+    pub fn reset_cursor(&mut self) -> Result<(), Error> {
+        self.set_system_cursor(SystemCursor::Arrow)
+    }
+
     /// Sets the size of the window.
     ///
-    pub fn set_size(&mut self, width: u32, height: u32) {
+    pub fn set_size(&mut self, width: u32, height: u32) -> Result<(), Error> {
         if self.width != width || self.height != height {
-            self.width  = width;
-            self.height = height;
+            self.sdl_window.set_size(width, height).map_err(|e| Error::Sdl(e.to_string()))?;
 
-            self.sdl_window.set_size(width, height).unwrap();
+            self.queue_resize();
         }
+
+        Ok(())
+    }
+
+    /// Queues a resize event if the window or drawable sizes have changed.
+    ///
+    fn queue_resize(&mut self) {
+        if self.update_size() {
+            self.events.push(AppEvent::Resized(self.width, self.height));
+        }
+    }
+
+    /// Reads the window and drawable sizes back from SDL, returns whether they have changed.
+    ///
+    fn update_size(&mut self) -> bool {
+        let (width, height)                   = self.sdl_window.size();
+        let (drawable_width, drawable_height) = self.sdl_window.drawable_size();
+
+        let changed = 
+            width           != self.width           ||
+            height          != self.height          ||
+            drawable_width  != self.drawable_width  ||
+            drawable_height != self.drawable_height;
+
+        self.width           = width;
+        self.height          = height;
+        self.drawable_width  = drawable_width;
+        self.drawable_height = drawable_height;
+
+        changed
     }
 
     /// Returns the size of the window in a tuple.
@@ -224,14 +946,51 @@ impl App {
         self.height
     }
 
+    /// Returns the size of the OpenGL drawable in pixels.
+    /// It is bigger than the window size on high-DPI displays.
+    ///
+    pub fn get_drawable_size(&self) -> (u32, u32) {
+        (self.drawable_width, self.drawable_height)
+    }
+
+    /// Returns how many pixels there are for each screen coordinate.
+    ///
+    pub fn get_scale_factor(&self) -> f32 {
+        if self.width == 0 {
+            return 1.0;
+        }
+
+        self.drawable_width as f32 / self.width as f32
+    }
+
+    /// Returns whether the window is minimized.
+    ///
+    pub fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
     /// Sets the title of the Main Window.
     ///
-    pub fn set_title(&mut self, title: &str) {
-        if self.title != String::from(title) {
+    pub fn set_title(&mut self, title: &str) -> Result<(), Error> {
+        if self.title != title {
             self.title = String::from(title);
 
-            self.sdl_window.set_title(title).unwrap();
+            self.sdl_window.set_title(title).map_err(|e| Error::Sdl(e.to_string()))?;
         }
+
+        Ok(())
+    }
+
+    /// Returns whether the Application is running without a visible window.
+    ///
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Returns the number of frames rendered since the Application started.
+    ///
+    pub fn get_frames_drawn(&self) -> u32 {
+        self.frames_drawn
     }
 
     /// Returns the title of the Main Window.
@@ -240,3 +999,13 @@ impl App {
         self.title.as_ref()
     }
 }
+
+/// Whether the event only changes the Input, the replay ignores it.
+///
+fn is_input(event: &Event) -> bool {
+    matches!(event,
+        Event::KeyDown { .. } | Event::KeyUp { .. } | Event::TextInput { .. } | Event::TextEditing { .. } |
+        Event::MouseButtonDown { .. } | Event::MouseButtonUp { .. } | Event::MouseMotion { .. } | Event::MouseWheel { .. } |
+        Event::ControllerButtonDown { .. } | Event::ControllerButtonUp { .. } | Event::ControllerAxisMotion { .. }
+    )
+}
@@ -0,0 +1,76 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{ Duration, Instant };
+
+/// Source of time used by the `Timer`.
+///
+/// The returned time must never go backwards, it only needs to be relative to any fixed point.
+///
+pub trait Clock {
+    /// Returns the time elapsed since the clock started.
+    ///
+    fn now(&self) -> Duration;
+}
+
+/// Clock that reads the monotonic clock of the PC, it is not affected by changes of the wall clock.
+///
+pub struct MonotonicClock {
+    start: Instant,
+}
+
+impl MonotonicClock {
+    /// Creates a new clock starting at zero.
+    ///
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Clock that only moves when told to, used for tests and deterministic runs.
+///
+/// Clones share the same time, so a copy can be kept to step a clock owned by a `Timer`.
+///
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    time: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    /// Creates a new clock stopped at zero.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward.
+    ///
+    pub fn advance(&self, duration: Duration) {
+        self.time.set(self.time.get() + duration);
+    }
+
+    /// Moves the clock forward by the given number of seconds.
+    ///
+    pub fn advance_secs(&self, seconds: f64) {
+        self.advance(Duration::from_secs_f64(seconds));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.time.get()
+    }
+}
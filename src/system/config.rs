@@ -0,0 +1,181 @@
+use crate::Error;
+use super::App;
+
+/// Fullscreen state of the Main Window.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Fullscreen {
+    /// A regular window.
+    Off,
+
+    /// A borderless window covering the desktop, without changing the video mode.
+    Desktop,
+
+    /// Real fullscreen, changing the video mode of the display.
+    Exclusive,
+}
+
+/// How the main loop updates and paces the Application.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LoopMode {
+    /// Updates with a fixed time step of `1 / frame_rate`.
+    /// At most `max_steps` updates run in a single frame to catch up, the remaining time is dropped.
+    Fixed { max_steps: u32 },
+
+    /// Updates once per frame with the measured delta time.
+    Variable,
+
+    /// Updates once per frame with the measured delta time, waiting for the vertical retrace instead of sleeping.
+    VSync,
+
+    /// Updates once per frame as fast as possible, without vsync or sleeping. Useful for benchmarks.
+    Uncapped,
+}
+
+impl Default for LoopMode {
+    fn default() -> Self {
+        Self::Fixed { max_steps: 8 }
+    }
+}
+
+/// Settings used to create an Application.
+///
+#[derive(Clone, Debug)]
+pub struct AppConfig {
+    pub title:      String,
+    pub width:      u32,
+    pub height:     u32,
+    pub frame_rate: f64,
+    pub loop_mode:  LoopMode,
+
+    pub resizable:  bool,
+    pub fullscreen: Fullscreen,
+    pub borderless: bool,
+    pub high_dpi:   bool,
+    pub position:   Option<(i32, i32)>,
+
+    pub vsync:        bool,
+    pub msaa_samples: u8,
+    pub gl_version:   (u8, u8),
+
+    pub headless: Option<u32>,
+}
+
+impl AppConfig {
+    /// Creates the default settings for a window with the given title and size.
+    ///
+    pub fn new(title: &str, width: u32, height: u32) -> Self {
+        Self {
+            title:      String::from(title),
+            width,
+            height,
+            frame_rate: 60.0,
+            loop_mode:  LoopMode::default(),
+
+            resizable:  false,
+            fullscreen: Fullscreen::Off,
+            borderless: false,
+            high_dpi:   false,
+            position:   None,
+
+            vsync:        false,
+            msaa_samples: 0,
+            gl_version:   (3, 3),
+
+            headless: None,
+        }
+    }
+
+    /// Sets the number of updates per second.
+    ///
+    pub fn frame_rate(mut self, frame_rate: f64) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// Sets how the main loop updates and paces the Application.
+    ///
+    pub fn loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
+    /// Whether the user can resize the window.
+    ///
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Sets the fullscreen state of the window.
+    ///
+    pub fn fullscreen(mut self, fullscreen: Fullscreen) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Whether the window is created without decorations.
+    ///
+    pub fn borderless(mut self, borderless: bool) -> Self {
+        self.borderless = borderless;
+        self
+    }
+
+    /// Whether the window uses the full resolution of high-DPI displays.
+    ///
+    pub fn high_dpi(mut self, high_dpi: bool) -> Self {
+        self.high_dpi = high_dpi;
+        self
+    }
+
+    /// Sets the initial position of the window in screen coordinates.
+    ///
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Whether buffer swaps wait for the vertical retrace of the display.
+    ///
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Sets the number of samples used for multisample anti-aliasing, 0 disables it.
+    ///
+    pub fn msaa(mut self, samples: u8) -> Self {
+        self.msaa_samples = samples;
+        self
+    }
+
+    /// Sets the version of the OpenGL Core context.
+    ///
+    pub fn gl_version(mut self, major: u8, minor: u8) -> Self {
+        self.gl_version = (major, minor);
+        self
+    }
+
+    /// Runs the Application against an offscreen OpenGL context for the given number of frames.
+    ///
+    /// The window is never shown and the Timer advances exactly one frame per iteration,
+    /// so the same Configuration can run on machines without a display (e.g. a CI server using Mesa).
+    ///
+    pub fn headless(mut self, frames: u32) -> Self {
+        self.headless = Some(frames);
+        self
+    }
+
+    /// Creates the Application described by these settings.
+    ///
+    pub fn build(self) -> Result<App, Error> {
+        App::new(self)
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self::new("battery", 800, 600)
+    }
+}
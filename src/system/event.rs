@@ -0,0 +1,111 @@
+use super::input::{ KeyCode, MouseButton, GamepadButton };
+use std::path::PathBuf;
+
+/// A key identified by the current keyboard layout, unlike a `KeyCode` which is a physical position.
+///
+pub type KeySym = sdl2::keyboard::Keycode;
+
+/// State of the modifier keys.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift:     bool,
+    pub ctrl:      bool,
+    pub alt:       bool,
+    pub gui:       bool,
+    pub caps_lock: bool,
+    pub num_lock:  bool,
+}
+
+impl Modifiers {
+    /// Converts the SDL modifier flags, left and right keys are merged.
+    ///
+    pub fn from_sdl(keymod: sdl2::keyboard::Mod) -> Self {
+        use sdl2::keyboard::Mod;
+
+        Self {
+            shift:     keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+            ctrl:      keymod.intersects(Mod::LCTRLMOD  | Mod::RCTRLMOD),
+            alt:       keymod.intersects(Mod::LALTMOD   | Mod::RALTMOD),
+            gui:       keymod.intersects(Mod::LGUIMOD   | Mod::RGUIMOD),
+            caps_lock: keymod.contains(Mod::CAPSMOD),
+            num_lock:  keymod.contains(Mod::NUMMOD),
+        }
+    }
+}
+
+/// A key has been pressed or released.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeyEvent {
+    pub key:       KeyCode,
+    pub symbol:    Option<KeySym>,
+    pub modifiers: Modifiers,
+    pub pressed:   bool,
+    pub repeat:    bool,
+}
+
+/// Text being composed with an input method (IME), before it is committed as text input.
+/// The cursor and the selection are counted in characters.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Composition {
+    pub text:      String,
+    pub cursor:    usize,
+    pub selection: usize,
+}
+
+/// A mouse button has been pressed or released.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MouseButtonEvent {
+    pub button:   MouseButton,
+    pub pressed:  bool,
+    pub clicks:   u8,
+    pub position: (f32, f32),
+}
+
+/// The mouse has moved, the delta is relative to the previous motion event.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MouseMotionEvent {
+    pub position: (f32, f32),
+    pub delta:    (f32, f32),
+}
+
+/// The mouse wheel has been scrolled.
+/// Positive values scroll to the right and away from the user, even when the system flips the wheel.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MouseWheelEvent {
+    pub delta:   (f32, f32),
+    pub flipped: bool,
+}
+
+/// A gamepad button has been pressed or released.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamepadButtonEvent {
+    pub id:      u32,
+    pub player:  usize,
+    pub button:  GamepadButton,
+    pub pressed: bool,
+}
+
+/// Events delivered by the Application to the `Configuration` hooks.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum AppEvent {
+    Resized(u32, u32),
+    FocusChanged(bool),
+    Key(KeyEvent),
+    TextInput(String),
+    TextEditing(Composition),
+    MouseButton(MouseButtonEvent),
+    MouseMotion(MouseMotionEvent),
+    MouseWheel(MouseWheelEvent),
+    FileDropped(PathBuf),
+    GamepadConnected(usize),
+    GamepadDisconnected(usize),
+    GamepadButton(GamepadButtonEvent),
+}
@@ -1,8 +1,48 @@
-use std::collections::HashSet;
+use super::action::{ AxisSide, Binding, InputMap };
+use super::event::{ Composition, KeyEvent, KeySym, Modifiers };
+use crate::Error;
+use sdl2::controller::GameController;
+use std::collections::{ HashMap, HashSet };
 
 pub type KeyCode       = sdl2::keyboard::Scancode;
 pub type MouseButton   = sdl2::mouse::MouseButton;
 pub type GamepadButton = sdl2::controller::Button;
+pub type GamepadAxis   = sdl2::controller::Axis;
+pub type SystemCursor  = sdl2::mouse::SystemCursor;
+
+/// How the positions of a stick close to its center are ignored.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Deadzone {
+    None,
+
+    /// Each axis is cut on its own, diagonals snap to the closest direction.
+    Axial(f32),
+
+    /// The distance of the stick to its center is cut, every direction can be reached.
+    Radial(f32),
+}
+
+/// Settings of the analog axes of the gamepads.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AxisSettings {
+    pub stick_deadzone:    Deadzone,
+    pub trigger_deadzone:  f32,
+    pub stick_threshold:   f32,
+    pub trigger_threshold: f32,
+}
+
+impl Default for AxisSettings {
+    fn default() -> Self {
+        Self {
+            stick_deadzone:    Deadzone::Radial(0.15),
+            trigger_deadzone:  0.05,
+            stick_threshold:   0.5,
+            trigger_threshold: 0.5,
+        }
+    }
+}
 
 /// Structure that handles gamepad button states.
 /// 
@@ -11,7 +51,23 @@ pub struct Gamepad {
     pressed:  HashSet<GamepadButton>,
     released: HashSet<GamepadButton>,
 
-    guid: u32,
+    axes:          HashMap<GamepadAxis, f32>,
+    previous_axes: HashMap<GamepadAxis, f32>,
+
+    id:         u32,
+    name:       String,
+    guid:       String,
+    connected:  bool,
+    controller: Option<GameController>,
+}
+
+/// The gamepad of a player, returned by `Input::gamepad`.
+///
+#[derive(Copy, Clone)]
+pub struct PlayerGamepad<'a> {
+    gamepad:  &'a Gamepad,
+    settings: &'a AxisSettings,
+    player:   usize,
 }
 
 /// Structure that handle the Input behavior.
@@ -20,15 +76,183 @@ pub struct Input {
     keyboard_down:     HashSet<KeyCode>,
     keyboard_pressed:  HashSet<KeyCode>,
     keyboard_released: HashSet<KeyCode>,
+    keyboard_repeated: HashSet<KeyCode>,
+
+    symbols_down:     HashSet<KeySym>,
+    symbols_pressed:  HashSet<KeySym>,
+    symbols_released: HashSet<KeySym>,
+    symbols_repeated: HashSet<KeySym>,
+
+    modifiers: Modifiers,
 
     mouse_down:     HashSet<MouseButton>,
     mouse_pressed:  HashSet<MouseButton>,
     mouse_released: HashSet<MouseButton>,
 
     gamepads:      Vec<Gamepad>,
-    gamepad_limit: u32,
+    gamepad_limit: usize,
+
+    text:        String,
+    composition: Option<Composition>,
+
+    mouse_delta: (f32, f32),
+    mouse_wheel: (f32, f32),
 
     pub mouse_position: (f32, f32),
+    pub actions:        InputMap,
+    pub axis_settings:  AxisSettings,
+}
+
+impl Gamepad {
+    /// Clears the state of the buttons and axes.
+    ///
+    fn clear(&mut self) {
+        self.down.clear();
+        self.pressed.clear();
+        self.released.clear();
+        self.axes.clear();
+        self.previous_axes.clear();
+    }
+
+    /// Returns the position of an axis after the deadzone, in this update or in the previous one.
+    ///
+    fn get_axis(&self, axis: GamepadAxis, settings: &AxisSettings, previous: bool) -> f32 {
+        let axes = if previous { &self.previous_axes } else { &self.axes };
+        let raw  = |axis| axes.get(&axis).copied().unwrap_or(0.0);
+
+        // The other axis of the same stick.
+        let other = match axis {
+            GamepadAxis::LeftX  => GamepadAxis::LeftY,
+            GamepadAxis::LeftY  => GamepadAxis::LeftX,
+            GamepadAxis::RightX => GamepadAxis::RightY,
+            GamepadAxis::RightY => GamepadAxis::RightX,
+
+            _ => return axial_deadzone(raw(axis), settings.trigger_deadzone),
+        };
+
+        match settings.stick_deadzone {
+            Deadzone::None         => raw(axis),
+            Deadzone::Axial(size)  => axial_deadzone(raw(axis), size),
+            Deadzone::Radial(size) => {
+                let length = raw(axis).hypot(raw(other));
+
+                if length <= size {
+                    return 0.0;
+                }
+
+                // Rescales the remaining distance, so the axis still goes smoothly from 0 to 1.
+                raw(axis) / length * ((length - size) / (1.0 - size)).min(1.0)
+            },
+        }
+    }
+
+    /// Whether an axis is pushed past the threshold, in this update or in the previous one.
+    ///
+    fn is_pushed(&self, axis: GamepadAxis, side: AxisSide, settings: &AxisSettings, previous: bool) -> bool {
+        let threshold = match axis {
+            GamepadAxis::TriggerLeft | GamepadAxis::TriggerRight => settings.trigger_threshold,
+
+            _ => settings.stick_threshold,
+        };
+
+        side.is_pushed(self.get_axis(axis, settings, previous), threshold)
+    }
+}
+
+impl PlayerGamepad<'_> {
+    /// Returns the player using the gamepad, starting at 0.
+    ///
+    pub fn get_player(&self) -> usize {
+        self.player
+    }
+
+    /// Returns the SDL instance id of the gamepad, the same one carried by the gamepad events.
+    ///
+    pub fn get_id(&self) -> u32 {
+        self.gamepad.id
+    }
+
+    /// Returns the name of the gamepad model.
+    ///
+    pub fn get_name(&self) -> &str {
+        &self.gamepad.name
+    }
+
+    /// Returns the GUID of the gamepad model, the same for every gamepad of that model.
+    ///
+    pub fn get_guid(&self) -> &str {
+        &self.gamepad.guid
+    }
+
+    /// Whether the gamepad is plugged in. The slot of an unplugged gamepad is kept for it.
+    ///
+    pub fn is_connected(&self) -> bool {
+        self.gamepad.connected
+    }
+
+    /// Check if the button is held down.
+    ///
+    pub fn button_down(&self, button: GamepadButton) -> bool {
+        self.gamepad.down.contains(&button)
+    }
+
+    /// Check if the button has been pressed.
+    ///
+    pub fn button_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepad.pressed.contains(&button)
+    }
+
+    /// Checks if the button has been released.
+    ///
+    pub fn button_released(&self, button: GamepadButton) -> bool {
+        self.gamepad.released.contains(&button)
+    }
+
+    /// Returns the position of an axis after the deadzone, from -1 to 1, or 0 to 1 for triggers.
+    ///
+    pub fn get_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad.get_axis(axis, self.settings, false)
+    }
+
+    /// Check if the axis is pushed to the side past its threshold.
+    ///
+    pub fn axis_down(&self, axis: GamepadAxis, side: AxisSide) -> bool {
+        self.gamepad.is_pushed(axis, side, self.settings, false)
+    }
+
+    /// Check if the axis has crossed its threshold towards the side.
+    ///
+    pub fn axis_pressed(&self, axis: GamepadAxis, side: AxisSide) -> bool {
+        self.gamepad.is_pushed(axis, side, self.settings, false) && !self.gamepad.is_pushed(axis, side, self.settings, true)
+    }
+
+    /// Check if the axis has come back from the side past its threshold.
+    ///
+    pub fn axis_released(&self, axis: GamepadAxis, side: AxisSide) -> bool {
+        !self.gamepad.is_pushed(axis, side, self.settings, false) && self.gamepad.is_pushed(axis, side, self.settings, true)
+    }
+}
+
+/// Converts an intensity from 0 to 1 to the range of the SDL motors.
+///
+fn to_intensity(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+}
+
+/// Converts a duration to the milliseconds used by SDL.
+///
+fn to_milliseconds(seconds: f64) -> u32 {
+    (seconds.max(0.0) * 1000.0).round() as u32
+}
+
+/// Cuts the values of an axis close to zero and rescales the rest.
+///
+fn axial_deadzone(value: f32, size: f32) -> f32 {
+    if value.abs() <= size {
+        return 0.0;
+    }
+
+    value.signum() * ((value.abs() - size) / (1.0 - size)).min(1.0)
 }
 
 impl Input {
@@ -39,15 +263,31 @@ impl Input {
             keyboard_down:     HashSet::new(),
             keyboard_pressed:  HashSet::new(),
             keyboard_released: HashSet::new(),
+            keyboard_repeated: HashSet::new(),
+
+            symbols_down:     HashSet::new(),
+            symbols_pressed:  HashSet::new(),
+            symbols_released: HashSet::new(),
+            symbols_repeated: HashSet::new(),
+
+            modifiers: Modifiers::default(),
 
             mouse_down:     HashSet::new(),
             mouse_pressed:  HashSet::new(),
             mouse_released: HashSet::new(),
 
             gamepads:      Vec::new(),
-            gamepad_limit: 0,
+            gamepad_limit: 8,
+
+            text:        String::new(),
+            composition: None,
+
+            mouse_delta: (0.0, 0.0),
+            mouse_wheel: (0.0, 0.0),
 
             mouse_position: (0.0, 0.0),
+            actions:        InputMap::new(),
+            axis_settings:  AxisSettings::default(),
         }
     }
 
@@ -56,16 +296,52 @@ impl Input {
     pub fn update(&mut self) {
         self.keyboard_pressed.clear();
         self.keyboard_released.clear();
+        self.keyboard_repeated.clear();
+
+        self.symbols_pressed.clear();
+        self.symbols_released.clear();
+        self.symbols_repeated.clear();
 
         self.mouse_pressed.clear();
         self.mouse_released.clear();
+        self.mouse_delta = (0.0, 0.0);
+        self.mouse_wheel = (0.0, 0.0);
+
+        self.text.clear();
+
+        for gamepad in self.gamepads.iter_mut() {
+            gamepad.pressed.clear();
+            gamepad.released.clear();
+            gamepad.previous_axes.clone_from(&gamepad.axes);
+        }
+    }
+
+    /// Releases everything held, forgets the text and the mouse motion, and disconnects the gamepads.
+    /// The actions, the axis settings and the player slots are kept.
+    ///
+    /// Returns the player, the SDL controller and the guid of the gamepads that had a controller, so they can be added back.
+    ///
+    pub(crate) fn reset(&mut self) -> Vec<(usize, GameController, String)> {
+        self.keyboard_down.clear();
+        self.symbols_down.clear();
+        self.mouse_down.clear();
+        self.modifiers   = Modifiers::default();
+        self.composition = None;
 
-        if self.gamepads.len() > 0 {
-            for gamepad in self.gamepads.iter_mut() {
-                gamepad.pressed.clear();
-                gamepad.released.clear();
+        self.update();
+
+        let mut controllers = Vec::new();
+
+        for (player, gamepad) in self.gamepads.iter_mut().enumerate() {
+            if let (true, Some(controller)) = (gamepad.connected, gamepad.controller.take()) {
+                controllers.push((player, controller, gamepad.guid.clone()));
             }
+
+            gamepad.clear();
+            gamepad.connected = false;
         }
+
+        controllers
     }
 
     //
@@ -74,22 +350,94 @@ impl Input {
 
     /// Check if the key is held down.
     ///
-    pub fn key_down(&mut self, key: KeyCode) -> bool {
+    pub fn key_down(&self, key: KeyCode) -> bool {
         self.keyboard_down.contains(&key)
     }
 
     /// Check if the key has been pressed.
     ///
-    pub fn key_pressed(&mut self, key: KeyCode) -> bool {
+    pub fn key_pressed(&self, key: KeyCode) -> bool {
         self.keyboard_pressed.contains(&key)
     }
 
     /// Check if the key has been released.
     ///
-    pub fn key_released(&mut self, key: KeyCode) -> bool {
+    pub fn key_released(&self, key: KeyCode) -> bool {
         self.keyboard_released.contains(&key)
     }
 
+    /// Check if the key has been pressed or repeated by the system while held, like in a text field.
+    ///
+    pub fn key_repeated(&self, key: KeyCode) -> bool {
+        self.keyboard_pressed.contains(&key) || self.keyboard_repeated.contains(&key)
+    }
+
+    /// Check if the key with the given symbol in the current keyboard layout is held down.
+    /// Use it for shortcuts that should follow the layout, like Ctrl+Z.
+    ///
+    pub fn symbol_down(&self, symbol: KeySym) -> bool {
+        self.symbols_down.contains(&symbol)
+    }
+
+    /// Check if the key with the given symbol has been pressed.
+    ///
+    pub fn symbol_pressed(&self, symbol: KeySym) -> bool {
+        self.symbols_pressed.contains(&symbol)
+    }
+
+    /// Check if the key with the given symbol has been released.
+    ///
+    pub fn symbol_released(&self, symbol: KeySym) -> bool {
+        self.symbols_released.contains(&symbol)
+    }
+
+    /// Check if the key with the given symbol has been pressed or repeated.
+    ///
+    pub fn symbol_repeated(&self, symbol: KeySym) -> bool {
+        self.symbols_pressed.contains(&symbol) || self.symbols_repeated.contains(&symbol)
+    }
+
+    /// Returns the state of the modifier keys in the last key event.
+    ///
+    pub fn get_modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Updates the keyboard with a key event.
+    /// A repeat of a key that isn't held, like when the focus comes back, is also a press of the key and its symbol.
+    ///
+    pub fn do_key_event(&mut self, event: &KeyEvent) {
+        self.modifiers = event.modifiers;
+
+        if event.pressed {
+            if event.repeat {
+                self.keyboard_repeated.insert(event.key);
+            }
+
+            self.do_key_down(event.key);
+        } else {
+            self.do_key_up(event.key);
+        }
+
+        let Some(symbol) = event.symbol else {
+            return;
+        };
+
+        if !event.pressed {
+            self.symbols_down.remove(&symbol);
+            self.symbols_released.insert(symbol);
+            return;
+        }
+
+        if event.repeat {
+            self.symbols_repeated.insert(symbol);
+        }
+
+        if self.symbols_down.insert(symbol) {
+            self.symbols_pressed.insert(symbol);
+        }
+    }
+
     /// Updates the given key when pressed.
     ///
     pub fn do_key_down(&mut self, key: KeyCode) {
@@ -109,34 +457,79 @@ impl Input {
         }
     }
 
+    //
+    // >> Text
+    //
+
+    /// Returns the UTF-8 text typed since the last update, empty while text input is stopped.
+    /// See `App::start_text_input`.
+    ///
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the text being composed with an input method, if any.
+    /// It should be drawn at the cursor, but not added to the text field until it is committed.
+    ///
+    pub fn get_composition(&self) -> Option<&Composition> {
+        self.composition.as_ref()
+    }
+
+    /// Called when text is typed or committed by an input method.
+    ///
+    pub fn do_text_input(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.composition = None;
+    }
+
+    /// Called when the text being composed changes, an empty text ends the composition.
+    ///
+    pub fn do_text_editing(&mut self, composition: Composition) {
+        self.composition = if composition.text.is_empty() { None } else { Some(composition) };
+    }
+
     //
     // >> Mouse
     //
 
     /// Check if the button is held down.
     ///
-    pub fn mouse_down(&mut self, button: MouseButton) -> bool {
+    pub fn mouse_down(&self, button: MouseButton) -> bool {
         self.mouse_down.contains(&button)
     }
 
     /// Check if the button has been pressed.
     ///
-    pub fn mouse_pressed(&mut self, button: MouseButton) -> bool {
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
         self.mouse_pressed.contains(&button)
     }
 
     /// Checks if the button has been released.
     ///
-    pub fn mouse_released(&mut self, button: MouseButton) -> bool {
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
         self.mouse_released.contains(&button)
     }
 
     /// Returns the mouse position.
     ///
-    pub fn get_mouse_position(&mut self) -> (f32, f32) {
+    pub fn get_mouse_position(&self) -> (f32, f32) {
         self.mouse_position
     }
 
+    /// Returns how much the mouse has moved since the last update.
+    /// It keeps working in relative mouse mode, where the position stops changing.
+    ///
+    pub fn get_mouse_delta(&self) -> (f32, f32) {
+        self.mouse_delta
+    }
+
+    /// Returns how much the wheel has scrolled since the last update.
+    /// Positive values scroll to the right and away from the user, with the fractions of precise touchpads.
+    ///
+    pub fn get_mouse_wheel(&self) -> (f32, f32) {
+        self.mouse_wheel
+    }
+
     /// Updates the given button when pressed.
     ///
     pub fn do_mouse_down(&mut self, button: MouseButton) {
@@ -162,101 +555,550 @@ impl Input {
         self.mouse_position = position.clone();
     }
 
+    /// Adds a relative motion of the mouse.
+    ///
+    pub fn do_mouse_delta(&mut self, delta: (f32, f32)) {
+        self.mouse_delta.0 += delta.0;
+        self.mouse_delta.1 += delta.1;
+    }
+
+    /// Adds a scroll of the mouse wheel.
+    ///
+    pub fn do_mouse_wheel(&mut self, delta: (f32, f32)) {
+        self.mouse_wheel.0 += delta.0;
+        self.mouse_wheel.1 += delta.1;
+    }
+
     //
     // >> Gamepad
     //
 
-    /// Check if the button is held down.
+    /// Returns the gamepad of the player, connected or not, if the player has ever had one.
     ///
-    pub fn button_down(&mut self, button: GamepadButton) -> bool {
-        for gamepad in self.gamepads.iter(){
-            if gamepad.down.contains(&button) {
-                return true;
-            }
-        }
+    pub fn gamepad(&self, player: usize) -> Option<PlayerGamepad<'_>> {
+        self.gamepads.get(player).map(|gamepad| PlayerGamepad {
+            gamepad,
+            settings: &self.axis_settings,
+            player,
+        })
+    }
 
-        false
+    /// Returns the gamepads that are plugged in.
+    ///
+    pub fn gamepads(&self) -> impl Iterator<Item = PlayerGamepad<'_>> {
+        (0..self.gamepads.len())
+            .filter_map(|player| self.gamepad(player))
+            .filter(|gamepad| gamepad.is_connected())
     }
 
-    /// Check if the button has been pressed.
+    /// Returns the player using the gamepad with the given instance id.
     ///
-    pub fn button_pressed(&mut self, button: GamepadButton) -> bool {
-        for gamepad in self.gamepads.iter(){
-            if gamepad.pressed.contains(&button) {
-                return true;
-            }
+    pub fn get_player(&self, id: u32) -> Option<usize> {
+        self.gamepads.iter().position(|gamepad| gamepad.connected && gamepad.id == id)
+    }
+
+    /// Returns the number of gamepads plugged in.
+    ///
+    pub fn get_gamepad_count(&self) -> usize {
+        self.gamepads().count()
+    }
+
+    /// Sets the maximum number of players, gamepads plugged in after that are ignored.
+    ///
+    pub fn set_gamepad_limit(&mut self, limit: usize) {
+        self.gamepad_limit = limit;
+    }
+
+    /// Check if the button is held down on any gamepad.
+    ///
+    pub fn button_down(&self, button: GamepadButton) -> bool {
+        self.gamepads().any(|gamepad| gamepad.button_down(button))
+    }
+
+    /// Check if the button has been pressed on any gamepad.
+    ///
+    pub fn button_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepads().any(|gamepad| gamepad.button_pressed(button))
+    }
+
+    /// Checks if the button has been released on any gamepad.
+    ///
+    pub fn button_released(&self, button: GamepadButton) -> bool {
+        self.gamepads().any(|gamepad| gamepad.button_released(button))
+    }
+
+    /// Returns the position of an axis after the deadzone, from -1 to 1, or 0 to 1 for triggers.
+    /// The axis pushed the furthest among the connected gamepads is returned.
+    ///
+    pub fn get_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepads()
+            .map(|gamepad| gamepad.get_axis(axis))
+            .fold(0.0, |furthest, value| if value.abs() > furthest.abs() { value } else { furthest })
+    }
+
+    /// Check if the axis is pushed to the side past its threshold, on any gamepad.
+    ///
+    pub fn axis_down(&self, axis: GamepadAxis, side: AxisSide) -> bool {
+        self.gamepads().any(|gamepad| gamepad.axis_down(axis, side))
+    }
+
+    /// Check if the axis has crossed its threshold towards the side, on any gamepad.
+    ///
+    pub fn axis_pressed(&self, axis: GamepadAxis, side: AxisSide) -> bool {
+        self.gamepads().any(|gamepad| gamepad.axis_pressed(axis, side))
+    }
+
+    /// Check if the axis has come back from the side past its threshold, on any gamepad.
+    ///
+    pub fn axis_released(&self, axis: GamepadAxis, side: AxisSide) -> bool {
+        self.gamepads().any(|gamepad| gamepad.axis_released(axis, side))
+    }
+
+    /// Gets the connected gamepad with the given instance id.
+    ///
+    fn get_gamepad(&mut self, id: u32) -> Option<&mut Gamepad> {
+        self.gamepads.iter_mut().find(|gamepad| gamepad.connected && gamepad.id == id)
+    }
+
+    /// Called when a gamepad is being added, returns the player it was given.
+    ///
+    /// A gamepad that comes back gets the slot of an unplugged gamepad of the same model,
+    /// otherwise the first free slot is taken.
+    ///
+    pub fn do_gamepad_added(&mut self, id: u32, name: &str, guid: &str) -> Option<usize> {
+        if let Some(player) = self.get_player(id) {
+            return Some(player);
         }
 
-        false
+        let player = self.gamepads.iter()
+            .position(|gamepad| !gamepad.connected && gamepad.guid == guid)
+            .or_else(|| self.gamepads.iter().position(|gamepad| !gamepad.connected));
+
+        let player = match player {
+            Some(player) => player,
+            None if self.gamepads.len() < self.gamepad_limit => {
+                self.gamepads.push(Gamepad {
+                    down:     HashSet::new(),
+                    pressed:  HashSet::new(),
+                    released: HashSet::new(),
+
+                    axes:          HashMap::new(),
+                    previous_axes: HashMap::new(),
+
+                    id,
+                    name:       String::new(),
+                    guid:       String::new(),
+                    connected:  false,
+                    controller: None,
+                });
+
+                self.gamepads.len() - 1
+            },
+            None => return None,
+        };
+
+        let gamepad = &mut self.gamepads[player];
+
+        gamepad.clear();
+        gamepad.id        = id;
+        gamepad.name      = name.to_string();
+        gamepad.guid      = guid.to_string();
+        gamepad.connected = true;
+
+        Some(player)
     }
 
-    /// Checks if the button has been released.
+    /// Called when a gamepad is being removed, returns the player that was using it.
     ///
-    pub fn button_released(&mut self, button: GamepadButton) -> bool {
-        for gamepad in self.gamepads.iter(){
-            if gamepad.released.contains(&button) {
-                return true;
-            }
+    pub fn do_gamepad_removed(&mut self, id: u32) -> Option<usize> {
+        let player  = self.get_player(id)?;
+        let gamepad = &mut self.gamepads[player];
+
+        gamepad.clear();
+        gamepad.connected  = false;
+        gamepad.controller = None;
+
+        Some(player)
+    }
+
+    /// Gives the SDL controller of a player to the Input, it is closed when the gamepad is removed.
+    ///
+    pub(crate) fn attach_controller(&mut self, player: usize, controller: GameController) {
+        if let Some(gamepad) = self.gamepads.get_mut(player) {
+            gamepad.controller = Some(controller);
         }
+    }
 
-        false
+    /// Gets the SDL controller of a connected player.
+    ///
+    fn get_controller(&self, player: usize) -> Option<&GameController> {
+        self.gamepads
+            .get(player)
+            .filter(|gamepad| gamepad.connected)
+            .and_then(|gamepad| gamepad.controller.as_ref())
     }
 
-    /// Gets a compatible gamepad.
+    /// Gets the SDL controller of a connected player, to drive its motors and light.
     ///
-    fn get_gamepad(&mut self, guid: u32) -> usize {
-        if self.gamepads.len() > 0 {
-            for i in 0..(self.gamepads.len() - 1) {
-                if self.gamepads[i].guid == guid {
-                    return i;
-                }
-            }
+    fn get_controller_mut(&mut self, player: usize) -> Option<&mut GameController> {
+        self.gamepads
+            .get_mut(player)
+            .filter(|gamepad| gamepad.connected)
+            .and_then(|gamepad| gamepad.controller.as_mut())
+    }
+
+    /// Shakes the gamepad of the player, the intensities of the low and high frequency motors go from 0 to 1.
+    /// A new rumble replaces the previous one, does nothing if the player has no gamepad.
+    ///
+    pub fn rumble(&mut self, player: usize, low: f32, high: f32, seconds: f64) -> Result<(), Error> {
+        match self.get_controller_mut(player) {
+            Some(controller) => controller
+                .set_rumble(to_intensity(low), to_intensity(high), to_milliseconds(seconds))
+                .map_err(|e| Error::Sdl(e.to_string())),
+            None => Ok(()),
         }
+    }
 
-        self.gamepad_limit as usize
+    /// Shakes the triggers of the gamepad of the player, on gamepads that have trigger motors.
+    ///
+    pub fn rumble_triggers(&mut self, player: usize, left: f32, right: f32, seconds: f64) -> Result<(), Error> {
+        match self.get_controller_mut(player) {
+            Some(controller) => controller
+                .set_rumble_triggers(to_intensity(left), to_intensity(right), to_milliseconds(seconds))
+                .map_err(|e| Error::Sdl(e.to_string())),
+            None => Ok(()),
+        }
     }
 
-    /// Called when a gamepad is being added.
+    /// Stops the motors of the gamepad of the player.
     ///
-    pub fn do_gamepad_added(&mut self, guid: u32) {
-        self.gamepads.push(Gamepad {
-            down:     HashSet::new(),
-            pressed:  HashSet::new(),
-            released: HashSet::new(),
+    pub fn stop_rumble(&mut self, player: usize) -> Result<(), Error> {
+        self.rumble(player, 0.0, 0.0, 0.0)?;
 
-            guid
-        });
+        if self.has_trigger_rumble(player) {
+            self.rumble_triggers(player, 0.0, 0.0, 0.0)?;
+        }
+
+        Ok(())
     }
 
-    /// Called when a gamepad is being removed.
+    /// Sets the color of the light of the gamepad of the player, the channels go from 0 to 1.
     ///
-    pub fn do_gamepad_removed(&mut self, guid: u32) {
-        let gamepad = self.get_gamepad(guid);
+    pub fn set_led(&mut self, player: usize, r: f32, g: f32, b: f32) -> Result<(), Error> {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
 
-        if gamepad < self.gamepads.len() {
-            self.gamepads.remove(gamepad);
+        match self.get_controller_mut(player) {
+            Some(controller) => controller
+                .set_led(channel(r), channel(g), channel(b))
+                .map_err(|e| Error::Sdl(e.to_string())),
+            None => Ok(()),
         }
     }
 
-    /// Called when a gamepad button is held down.
+    /// Whether the gamepad of the player has rumble motors.
+    ///
+    pub fn has_rumble(&self, player: usize) -> bool {
+        self.get_controller(player).is_some_and(|controller| controller.has_rumble())
+    }
+
+    /// Whether the gamepad of the player has motors in the triggers.
+    ///
+    pub fn has_trigger_rumble(&self, player: usize) -> bool {
+        self.get_controller(player).is_some_and(|controller| controller.has_rumble_triggers())
+    }
+
+    /// Whether the gamepad of the player has a light that can be colored.
     ///
-    pub fn do_gamepad_down(&mut self, guid: u32, button: GamepadButton) {
-        let gamepad = self.get_gamepad(guid);
+    pub fn has_led(&self, player: usize) -> bool {
+        self.get_controller(player).is_some_and(|controller| controller.has_led())
+    }
 
-        if gamepad != self.gamepad_limit as usize {
-            self.gamepads[gamepad].down.insert(button);
-            self.gamepads[gamepad].pressed.insert(button);
+    /// Called when a gamepad button is held down.
+    ///
+    pub fn do_gamepad_down(&mut self, id: u32, button: GamepadButton) {
+        if let Some(gamepad) = self.get_gamepad(id) {
+            if gamepad.down.insert(button) {
+                gamepad.pressed.insert(button);
+            }
         }
     }
 
     /// Called when a gamepad button is being released.
     ///
-    pub fn do_gamepad_up(&mut self, guid: u32, button: GamepadButton) {
-        let gamepad = self.get_gamepad(guid);
+    pub fn do_gamepad_up(&mut self, id: u32, button: GamepadButton) {
+        if let Some(gamepad) = self.get_gamepad(id) {
+            gamepad.down.remove(&button);
+            gamepad.released.insert(button);
+        }
+    }
 
-        if gamepad != self.gamepad_limit as usize {
-            self.gamepads[gamepad].down.remove(&button);
-            self.gamepads[gamepad].released.insert(button);
+    /// Called when a gamepad axis moves, the value goes from -1 to 1.
+    ///
+    pub fn do_gamepad_axis(&mut self, id: u32, axis: GamepadAxis, value: f32) {
+        if let Some(gamepad) = self.get_gamepad(id) {
+            gamepad.axes.insert(axis, value);
         }
     }
-}
\ No newline at end of file
+
+    //
+    // >> Actions
+    //
+
+    /// Check if any input bound to the action is held down.
+    ///
+    pub fn action_down(&self, action: &str) -> bool {
+        self.actions.get_bindings(action).iter().any(|binding| self.binding_down(*binding))
+    }
+
+    /// Check if the action has been pressed, holding another input bound to it doesn't count.
+    ///
+    pub fn action_pressed(&self, action: &str) -> bool {
+        let bindings = self.actions.get_bindings(action);
+
+        bindings.iter().any(|binding| self.binding_pressed(*binding))
+            && !bindings.iter().any(|binding| self.binding_down(*binding) && !self.binding_pressed(*binding))
+    }
+
+    /// Check if the action has been released and no other input bound to it is held down.
+    ///
+    pub fn action_released(&self, action: &str) -> bool {
+        let bindings = self.actions.get_bindings(action);
+
+        bindings.iter().any(|binding| self.binding_released(*binding))
+            && !bindings.iter().any(|binding| self.binding_down(*binding))
+    }
+
+    /// Returns the value of a named axis, from -1 to 1.
+    /// The binding pushed the furthest wins, so a keyboard and a stick can be used together.
+    ///
+    pub fn action_axis(&self, axis: &str) -> f32 {
+        self.actions
+            .get_axis_bindings(axis)
+            .iter()
+            .map(|binding| binding.value(self))
+            .fold(0.0, |furthest, value| if value.abs() > furthest.abs() { value } else { furthest })
+    }
+
+    /// Check if the binding is held down.
+    ///
+    pub fn binding_down(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(key)            => self.key_down(key),
+            Binding::Mouse(button)       => self.mouse_down(button),
+            Binding::Button(button)      => self.button_down(button),
+            Binding::Axis(axis, side)    => self.axis_down(axis, side),
+        }
+    }
+
+    /// Check if the binding has been pressed.
+    ///
+    pub fn binding_pressed(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(key)            => self.key_pressed(key),
+            Binding::Mouse(button)       => self.mouse_pressed(button),
+            Binding::Button(button)      => self.button_pressed(button),
+            Binding::Axis(axis, side)    => self.axis_pressed(axis, side),
+        }
+    }
+
+    /// Check if the binding has been released.
+    ///
+    pub fn binding_released(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(key)            => self.key_released(key),
+            Binding::Mouse(button)       => self.mouse_released(button),
+            Binding::Button(button)      => self.button_released(button),
+            Binding::Axis(axis, side)    => self.axis_released(axis, side),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzones_and_thresholds() {
+        let mut settings = AxisSettings::default();
+        let mut gamepad  = Gamepad {
+            down:     HashSet::new(),
+            pressed:  HashSet::new(),
+            released: HashSet::new(),
+
+            axes:          HashMap::new(),
+            previous_axes: HashMap::new(),
+
+            id:         0,
+            name:       String::new(),
+            guid:       String::new(),
+            connected:  true,
+            controller: None,
+        };
+
+        // Inside the radial deadzone, but outside of an axial one.
+        gamepad.axes.insert(GamepadAxis::LeftX, 0.1);
+        gamepad.axes.insert(GamepadAxis::LeftY, 0.1);
+        assert_eq!(gamepad.get_axis(GamepadAxis::LeftX, &settings, false), 0.0);
+
+        settings.stick_deadzone = Deadzone::Axial(0.05);
+        assert!((gamepad.get_axis(GamepadAxis::LeftX, &settings, false) - 0.05 / 0.95).abs() < 1e-5);
+
+        gamepad.axes.insert(GamepadAxis::LeftX, -1.0);
+        assert_eq!(gamepad.get_axis(GamepadAxis::LeftX, &settings, false), -1.0);
+        assert!(gamepad.is_pushed(GamepadAxis::LeftX, AxisSide::Negative, &settings, false));
+        assert!(!gamepad.is_pushed(GamepadAxis::LeftX, AxisSide::Negative, &settings, true));
+
+        settings.trigger_threshold = 0.8;
+        gamepad.axes.insert(GamepadAxis::TriggerRight, 0.7);
+        assert!(!gamepad.is_pushed(GamepadAxis::TriggerRight, AxisSide::Positive, &settings, false));
+    }
+
+    #[test]
+    fn players_keep_their_slots() {
+        let mut input = Input::new();
+
+        assert_eq!(input.do_gamepad_added(10, "Pad", "pad"), Some(0));
+        assert_eq!(input.do_gamepad_added(11, "Stick", "stick"), Some(1));
+        assert_eq!(input.do_gamepad_added(10, "Pad", "pad"), Some(0));
+
+        input.do_gamepad_down(11, GamepadButton::A);
+        assert!(input.button_down(GamepadButton::A));
+        assert!(input.gamepad(1).unwrap().button_pressed(GamepadButton::A));
+        assert!(!input.gamepad(0).unwrap().button_down(GamepadButton::A));
+
+        // The first player unplugs, a new gamepad of another model takes the free slot.
+        assert_eq!(input.do_gamepad_removed(10), Some(0));
+        assert!(!input.gamepad(0).unwrap().is_connected());
+        assert_eq!(input.do_gamepad_added(12, "Stick", "stick"), Some(0));
+        assert_eq!(input.get_player(12), Some(0));
+        assert_eq!(input.get_gamepad_count(), 2);
+
+        // The second player comes back to the same slot.
+        input.do_gamepad_removed(11);
+        assert!(!input.button_down(GamepadButton::A));
+        assert_eq!(input.do_gamepad_added(13, "Stick", "stick"), Some(1));
+        assert_eq!(input.gamepad(1).unwrap().get_id(), 13);
+
+        input.set_gamepad_limit(2);
+        assert_eq!(input.do_gamepad_added(14, "Pad", "pad"), None);
+    }
+
+    #[test]
+    fn text_is_collected_until_the_update() {
+        let mut input = Input::new();
+
+        input.do_text_editing(Composition { text: "ni".to_string(), cursor: 2, selection: 0 });
+        assert_eq!(input.get_composition().map(|composition| composition.text.as_str()), Some("ni"));
+
+        input.do_text_input("你");
+        input.do_text_input("好");
+        assert_eq!(input.get_text(), "你好");
+        assert!(input.get_composition().is_none());
+
+        input.update();
+        assert_eq!(input.get_text(), "");
+    }
+
+    #[test]
+    fn reset_releases_everything() {
+        let mut input = Input::new();
+
+        input.do_key_event(&KeyEvent {
+            key:       KeyCode::A,
+            symbol:    Some(KeySym::A),
+            modifiers: Modifiers { shift: true, ..Modifiers::default() },
+            pressed:   true,
+            repeat:    false,
+        });
+        input.do_mouse_down(MouseButton::Left);
+        input.do_mouse_delta((1.0, 1.0));
+        input.do_text_input("a");
+        input.do_gamepad_added(7, "Pad", "abc");
+        input.do_gamepad_down(7, GamepadButton::A);
+
+        assert!(input.reset().is_empty());
+        assert!(!input.key_down(KeyCode::A) && !input.key_pressed(KeyCode::A));
+        assert!(!input.symbol_down(KeySym::A));
+        assert!(!input.get_modifiers().shift);
+        assert!(!input.mouse_down(MouseButton::Left));
+        assert_eq!(input.get_mouse_delta(), (0.0, 0.0));
+        assert_eq!(input.get_text(), "");
+        assert_eq!(input.get_player(7), None);
+
+        // The player keeps the slot when the gamepad comes back.
+        assert_eq!(input.do_gamepad_added(8, "Pad", "abc"), Some(0));
+        assert!(!input.button_down(GamepadButton::A));
+    }
+
+    #[test]
+    fn mouse_motion_and_wheel_add_up_until_the_update() {
+        let mut input = Input::new();
+
+        input.do_mouse_delta((3.0, -2.0));
+        input.do_mouse_delta((1.5, 4.0));
+        input.do_mouse_delta((-0.5, 0.0));
+        input.do_mouse_wheel((0.0, 1.0));
+        input.do_mouse_wheel((0.25, 0.5));
+        assert_eq!(input.get_mouse_delta(), (4.0, 2.0));
+        assert_eq!(input.get_mouse_wheel(), (0.25, 1.5));
+
+        input.update();
+        assert_eq!(input.get_mouse_delta(), (0.0, 0.0));
+        assert_eq!(input.get_mouse_wheel(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn key_events_update_symbols_and_repeats() {
+        let mut input = Input::new();
+        let mut event = KeyEvent {
+            key:       KeyCode::Z,
+            symbol:    Some(KeySym::Y),
+            modifiers: Modifiers { ctrl: true, ..Modifiers::default() },
+            pressed:   true,
+            repeat:    false,
+        };
+
+        input.do_key_event(&event);
+        assert!(input.key_pressed(KeyCode::Z));
+        assert!(input.symbol_pressed(KeySym::Y));
+        assert!(input.get_modifiers().ctrl);
+
+        input.update();
+        event.repeat = true;
+        input.do_key_event(&event);
+        assert!(!input.key_pressed(KeyCode::Z));
+        assert!(input.key_repeated(KeyCode::Z));
+        assert!(input.symbol_repeated(KeySym::Y));
+
+        input.update();
+        event.pressed   = false;
+        event.repeat    = false;
+        event.modifiers = Modifiers::default();
+        input.do_key_event(&event);
+        assert!(input.symbol_released(KeySym::Y));
+        assert!(!input.symbol_down(KeySym::Y));
+        assert!(!input.get_modifiers().ctrl);
+
+        // A repeat of a key that isn't held presses both the key and its symbol.
+        input.update();
+        event.pressed = true;
+        event.repeat  = true;
+        input.do_key_event(&event);
+        assert!(input.key_down(KeyCode::Z) && input.key_pressed(KeyCode::Z));
+        assert!(input.symbol_down(KeySym::Y) && input.symbol_pressed(KeySym::Y));
+    }
+
+    #[test]
+    fn rumble_values_convert_to_sdl() {
+        assert_eq!(to_intensity(0.0), 0);
+        assert_eq!(to_intensity(0.5), 32768);
+        assert_eq!(to_intensity(1.0), u16::MAX);
+        assert_eq!(to_intensity(-1.0), 0);
+        assert_eq!(to_intensity(2.0), u16::MAX);
+
+        assert_eq!(to_milliseconds(0.25), 250);
+        assert_eq!(to_milliseconds(0.0004), 0);
+        assert_eq!(to_milliseconds(0.0006), 1);
+        assert_eq!(to_milliseconds(-1.0), 0);
+    }
+}
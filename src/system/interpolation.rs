@@ -0,0 +1,132 @@
+use glam::Vec2;
+
+/// Values that can be linearly interpolated.
+///
+pub trait Lerp {
+    /// Returns the value between `self` (t = 0) and `other` (t = 1).
+    ///
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t as f64
+    }
+}
+
+impl Lerp for (f32, f32) {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (self.0.lerp(&other.0, t), self.1.lerp(&other.1, t))
+    }
+}
+
+impl Lerp for (f32, f32, f32) {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (self.0.lerp(&other.0, t), self.1.lerp(&other.1, t), self.2.lerp(&other.2, t))
+    }
+}
+
+impl Lerp for (f32, f32, f32, f32) {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        (
+            self.0.lerp(&other.0, t),
+            self.1.lerp(&other.1, t),
+            self.2.lerp(&other.2, t),
+            self.3.lerp(&other.3, t),
+        )
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec2::lerp(*self, *other, t)
+    }
+}
+
+/// Stores the state of the two last updates so it can be drawn between them.
+///
+/// Call `set` once per update and `get` with `Timer::get_alpha` when rendering,
+/// this removes the stutter of a fixed update rate on faster displays.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Interpolated<T: Lerp + Copy> {
+    previous: T,
+    current:  T,
+}
+
+impl<T: Lerp + Copy> Interpolated<T> {
+    /// Creates a new state with no motion.
+    ///
+    pub fn new(value: T) -> Self {
+        Self {
+            previous: value,
+            current:  value,
+        }
+    }
+
+    /// Stores the value of this update, the old value becomes the previous.
+    ///
+    pub fn set(&mut self, value: T) {
+        self.previous = self.current;
+        self.current  = value;
+    }
+
+    /// Moves to the value without interpolating, useful for teleports.
+    ///
+    pub fn reset(&mut self, value: T) {
+        self.previous = value;
+        self.current  = value;
+    }
+
+    /// Returns the value at the given point between the previous and the current update.
+    ///
+    pub fn get(&self, alpha: f64) -> T {
+        self.previous.lerp(&self.current, alpha as f32)
+    }
+
+    /// Returns the value of the last update.
+    ///
+    pub fn current(&self) -> T {
+        self.current
+    }
+
+    /// Returns the value of the update before the last one.
+    ///
+    pub fn previous(&self) -> T {
+        self.previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_tuples() {
+        assert_eq!((0.0, 10.0).lerp(&(10.0, 20.0), 0.5), (5.0, 15.0));
+        assert_eq!((0.0, 0.0, 0.0, 1.0).lerp(&(1.0, 1.0, 1.0, 0.0), 0.25), (0.25, 0.25, 0.25, 0.75));
+    }
+
+    #[test]
+    fn interpolated_keeps_two_states() {
+        let mut position = Interpolated::new(0.0_f32);
+
+        position.set(10.0);
+        assert_eq!(position.get(0.0), 0.0);
+        assert_eq!(position.get(0.5), 5.0);
+        assert_eq!(position.get(1.0), 10.0);
+
+        position.set(20.0);
+        assert_eq!(position.previous(), 10.0);
+        assert_eq!(position.get(0.5), 15.0);
+
+        position.reset(0.0);
+        assert_eq!(position.get(0.5), 0.0);
+    }
+}
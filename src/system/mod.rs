@@ -1,8 +1,32 @@
 pub mod input;
-pub use input::{ KeyCode, MouseButton, Input };
+pub use input::{ KeyCode, MouseButton, GamepadButton, GamepadAxis, SystemCursor, Deadzone, AxisSettings, Input, PlayerGamepad };
+
+pub mod action;
+pub use action::{ Binding, AxisBinding, AxisSide, InputMap };
+
+pub mod event;
+pub use event::{ AppEvent, KeySym, Modifiers, KeyEvent, Composition, MouseButtonEvent, MouseMotionEvent, MouseWheelEvent, GamepadButtonEvent };
+
+pub mod recording;
+pub use recording::{ InputEvent, InputRecording };
+
+pub mod clock;
+pub use clock::{ Clock, MonotonicClock, ManualClock };
+
+pub mod schedule;
+pub use schedule::ScheduleHandle;
 
 pub mod timer;
 pub use timer::Timer;
 
+pub mod interpolation;
+pub use interpolation::{ Lerp, Interpolated };
+
+pub mod config;
+pub use config::{ AppConfig, Fullscreen, LoopMode };
+
 pub mod app;
-pub use app::{ App, Configuration };
\ No newline at end of file
+pub use app::{ App, Configuration };
+
+pub mod scene;
+pub use scene::{ Scene, SceneAction, SceneManager, Transition, WipeDirection };
@@ -0,0 +1,455 @@
+use super::action::{ KEY_CODES, MOUSE_BUTTONS, GAMEPAD_BUTTONS, GAMEPAD_AXES };
+use super::event::{ Composition, KeyEvent, KeySym, Modifiers };
+use super::input::{ Input, KeyCode, MouseButton, GamepadButton, GamepadAxis };
+use crate::Error;
+use std::fmt;
+use std::path::Path;
+
+/// Identifies input recording files, followed by the format version.
+///
+const MAGIC:   &[u8; 4] = b"BTRY";
+const VERSION: u8       = 1;
+
+/// A change to the input state, in the order it reached the Input.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    Key(KeyEvent),
+    Text(String),
+    TextEditing(Composition),
+
+    MouseDown(MouseButton),
+    MouseUp(MouseButton),
+    MouseMotion { position: (f32, f32), delta: (f32, f32) },
+    MouseWheel((f32, f32)),
+
+    GamepadAdded { id: u32, name: String, guid: String },
+    GamepadRemoved(u32),
+    GamepadDown(u32, GamepadButton),
+    GamepadUp(u32, GamepadButton),
+    GamepadAxis(u32, GamepadAxis, f32),
+}
+
+impl InputEvent {
+    /// Applies the event to the Input.
+    ///
+    pub fn apply(&self, input: &mut Input) {
+        match self {
+            Self::Key(event)                      => input.do_key_event(event),
+            Self::Text(text)                      => input.do_text_input(text),
+            Self::TextEditing(composition)        => input.do_text_editing(composition.clone()),
+            Self::MouseDown(button)               => input.do_mouse_down(*button),
+            Self::MouseUp(button)                 => input.do_mouse_up(*button),
+            Self::MouseMotion { position, delta } => {
+                input.do_mouse_move(*position);
+                input.do_mouse_delta(*delta);
+            },
+            Self::MouseWheel(delta)               => input.do_mouse_wheel(*delta),
+            Self::GamepadAdded { id, name, guid } => { input.do_gamepad_added(*id, name, guid); },
+            Self::GamepadRemoved(id)              => { input.do_gamepad_removed(*id); },
+            Self::GamepadDown(id, button)         => input.do_gamepad_down(*id, *button),
+            Self::GamepadUp(id, button)           => input.do_gamepad_up(*id, *button),
+            Self::GamepadAxis(id, axis, value)    => input.do_gamepad_axis(*id, *axis, *value),
+        }
+    }
+}
+
+/// The input received by each update of an Application, see `App::start_recording` and `App::replay`.
+///
+/// Replaying a recording through the fixed time step gives the game the exact same input on the same updates,
+/// as long as the game starts from the same state and doesn't depend on anything else, like the wall clock.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    frame_rate: f64,
+    ticks:      Vec<Vec<InputEvent>>,
+}
+
+impl InputRecording {
+    /// Creates an empty recording for an Application updating `frame_rate` times per second.
+    ///
+    pub fn new(frame_rate: f64) -> Self {
+        Self { frame_rate, ticks: Vec::new() }
+    }
+
+    /// Adds the events received by the next update.
+    ///
+    pub fn push_tick(&mut self, events: Vec<InputEvent>) {
+        self.ticks.push(events);
+    }
+
+    /// Returns the events received by the given update.
+    ///
+    pub fn get_tick(&self, tick: usize) -> Option<&[InputEvent]> {
+        self.ticks.get(tick).map(Vec::as_slice)
+    }
+
+    /// Returns the number of recorded updates.
+    ///
+    pub fn get_tick_count(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// Returns the number of updates per second of the recorded Application.
+    ///
+    pub fn get_frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    /// Takes the recorded updates, in order.
+    ///
+    pub(crate) fn into_ticks(self) -> std::vec::IntoIter<Vec<InputEvent>> {
+        self.ticks.into_iter()
+    }
+
+    /// Encodes the recording, updates without input take a single byte.
+    ///
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.frame_rate.to_le_bytes());
+        write_varint(&mut bytes, self.ticks.len() as u32);
+
+        for events in &self.ticks {
+            write_varint(&mut bytes, events.len() as u32);
+
+            for event in events {
+                write_event(&mut bytes, event)?;
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes a recording made by `to_bytes`.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes, position: 0 };
+
+        if reader.take(MAGIC.len())? != MAGIC || reader.u8()? != VERSION {
+            return Err(Error::Other("not an input recording".to_string()));
+        }
+
+        let frame_rate = f64::from_le_bytes(reader.array()?);
+        let tick_count = reader.varint()?;
+        let mut ticks  = Vec::new();
+
+        for _ in 0..tick_count {
+            let event_count = reader.varint()?;
+            let mut events  = Vec::new();
+
+            for _ in 0..event_count {
+                events.push(reader.event()?);
+            }
+
+            ticks.push(events);
+        }
+
+        Ok(Self { frame_rate, ticks })
+    }
+
+    /// Saves the recording to a file.
+    ///
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Loads a recording from a file.
+    ///
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+// Event tags.
+const KEY:             u8 = 0;
+const TEXT:            u8 = 1;
+const TEXT_EDITING:    u8 = 2;
+const MOUSE_DOWN:      u8 = 3;
+const MOUSE_UP:        u8 = 4;
+const MOUSE_MOTION:    u8 = 5;
+const MOUSE_WHEEL:     u8 = 6;
+const GAMEPAD_ADDED:   u8 = 7;
+const GAMEPAD_REMOVED: u8 = 8;
+const GAMEPAD_DOWN:    u8 = 9;
+const GAMEPAD_UP:      u8 = 10;
+const GAMEPAD_AXIS:    u8 = 11;
+
+fn write_event(bytes: &mut Vec<u8>, event: &InputEvent) -> Result<(), Error> {
+    match event {
+        InputEvent::Key(event) => {
+            let modifiers = &event.modifiers;
+            let flags     = [
+                modifiers.shift, modifiers.ctrl, modifiers.alt, modifiers.gui,
+                modifiers.caps_lock, modifiers.num_lock, event.pressed, event.repeat,
+            ];
+
+            bytes.push(KEY);
+            write_varint(bytes, event.key as u32);
+            write_varint(bytes, event.symbol.map_or(0, |symbol| symbol.into_i32()) as u32);
+            bytes.push(flags.iter().rev().fold(0, |bits, flag| bits << 1 | *flag as u8));
+        },
+        InputEvent::Text(text) => {
+            bytes.push(TEXT);
+            write_string(bytes, text);
+        },
+        InputEvent::TextEditing(composition) => {
+            bytes.push(TEXT_EDITING);
+            write_string(bytes, &composition.text);
+            write_varint(bytes, composition.cursor as u32);
+            write_varint(bytes, composition.selection as u32);
+        },
+        InputEvent::MouseDown(button) => {
+            bytes.push(MOUSE_DOWN);
+            bytes.push(index_of(&MOUSE_BUTTONS, button)?);
+        },
+        InputEvent::MouseUp(button) => {
+            bytes.push(MOUSE_UP);
+            bytes.push(index_of(&MOUSE_BUTTONS, button)?);
+        },
+        InputEvent::MouseMotion { position, delta } => {
+            bytes.push(MOUSE_MOTION);
+
+            for value in [position.0, position.1, delta.0, delta.1] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        },
+        InputEvent::MouseWheel(delta) => {
+            bytes.push(MOUSE_WHEEL);
+            bytes.extend_from_slice(&delta.0.to_le_bytes());
+            bytes.extend_from_slice(&delta.1.to_le_bytes());
+        },
+        InputEvent::GamepadAdded { id, name, guid } => {
+            bytes.push(GAMEPAD_ADDED);
+            write_varint(bytes, *id);
+            write_string(bytes, name);
+            write_string(bytes, guid);
+        },
+        InputEvent::GamepadRemoved(id) => {
+            bytes.push(GAMEPAD_REMOVED);
+            write_varint(bytes, *id);
+        },
+        InputEvent::GamepadDown(id, button) => {
+            bytes.push(GAMEPAD_DOWN);
+            write_varint(bytes, *id);
+            bytes.push(index_of(&GAMEPAD_BUTTONS, button)?);
+        },
+        InputEvent::GamepadUp(id, button) => {
+            bytes.push(GAMEPAD_UP);
+            write_varint(bytes, *id);
+            bytes.push(index_of(&GAMEPAD_BUTTONS, button)?);
+        },
+        InputEvent::GamepadAxis(id, axis, value) => {
+            bytes.push(GAMEPAD_AXIS);
+            write_varint(bytes, *id);
+            bytes.push(index_of(&GAMEPAD_AXES, axis)?);
+            bytes.extend_from_slice(&value.to_le_bytes());
+        },
+    }
+
+    Ok(())
+}
+
+/// Writes an unsigned LEB128 number, values under 128 take a single byte.
+///
+fn write_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+fn write_string(bytes: &mut Vec<u8>, text: &str) {
+    write_varint(bytes, text.len() as u32);
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+/// Buttons and axes are stored as their position in the lists of known values,
+/// the SDL conversions can't be used without initializing SDL.
+///
+fn index_of<T: PartialEq + fmt::Debug>(values: &[T], value: &T) -> Result<u8, Error> {
+    match values.iter().position(|known| known == value) {
+        Some(index) => Ok(index as u8),
+        None        => Err(Error::Other(format!("{:?} can't be recorded", value))),
+    }
+}
+
+/// Reads a recording, any truncated or unknown data is an error.
+///
+struct Reader<'a> {
+    bytes:    &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn invalid() -> Error {
+        Error::Other("invalid input recording".to_string())
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.bytes
+            .get(self.position..self.position + length)
+            .ok_or_else(Self::invalid)?;
+
+        self.position += length;
+
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn varint(&mut self) -> Result<u32, Error> {
+        let mut value = 0;
+
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+
+            value |= ((byte & 0x7f) as u32) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(Self::invalid())
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let length = self.varint()? as usize;
+
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|_| Self::invalid())
+    }
+
+    fn known<T: Copy>(&mut self, values: &[T]) -> Result<T, Error> {
+        values.get(self.u8()? as usize).copied().ok_or_else(Self::invalid)
+    }
+
+    fn event(&mut self) -> Result<InputEvent, Error> {
+        Ok(match self.u8()? {
+            KEY => {
+                let code = self.varint()? as i32;
+
+                if !KEY_CODES.iter().any(|codes| codes.contains(&code)) {
+                    return Err(Self::invalid());
+                }
+
+                let key    = KeyCode::from_i32(code).ok_or_else(Self::invalid)?;
+                let symbol = KeySym::from_i32(self.varint()? as i32);
+                let flags  = self.u8()?;
+                let flag   = |bit: u8| flags & (1 << bit) != 0;
+
+                InputEvent::Key(KeyEvent {
+                    key,
+                    symbol,
+                    modifiers: Modifiers {
+                        shift:     flag(0),
+                        ctrl:      flag(1),
+                        alt:       flag(2),
+                        gui:       flag(3),
+                        caps_lock: flag(4),
+                        num_lock:  flag(5),
+                    },
+                    pressed: flag(6),
+                    repeat:  flag(7),
+                })
+            },
+            TEXT         => InputEvent::Text(self.string()?),
+            TEXT_EDITING => InputEvent::TextEditing(Composition {
+                text:      self.string()?,
+                cursor:    self.varint()? as usize,
+                selection: self.varint()? as usize,
+            }),
+            MOUSE_DOWN   => InputEvent::MouseDown(self.known(&MOUSE_BUTTONS)?),
+            MOUSE_UP     => InputEvent::MouseUp(self.known(&MOUSE_BUTTONS)?),
+            MOUSE_MOTION => InputEvent::MouseMotion {
+                position: (self.f32()?, self.f32()?),
+                delta:    (self.f32()?, self.f32()?),
+            },
+            MOUSE_WHEEL     => InputEvent::MouseWheel((self.f32()?, self.f32()?)),
+            GAMEPAD_ADDED   => InputEvent::GamepadAdded { id: self.varint()?, name: self.string()?, guid: self.string()? },
+            GAMEPAD_REMOVED => InputEvent::GamepadRemoved(self.varint()?),
+            GAMEPAD_DOWN    => InputEvent::GamepadDown(self.varint()?, self.known(&GAMEPAD_BUTTONS)?),
+            GAMEPAD_UP      => InputEvent::GamepadUp(self.varint()?, self.known(&GAMEPAD_BUTTONS)?),
+            GAMEPAD_AXIS    => InputEvent::GamepadAxis(self.varint()?, self.known(&GAMEPAD_AXES)?, self.f32()?),
+
+            _ => return Err(Self::invalid()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recordings_survive_encoding_and_drive_the_input() {
+        let mut recording = InputRecording::new(60.0);
+
+        recording.push_tick(vec![
+            InputEvent::Key(KeyEvent {
+                key:       KeyCode::Space,
+                symbol:    Some(KeySym::SPACE),
+                modifiers: Modifiers { shift: true, ..Modifiers::default() },
+                pressed:   true,
+                repeat:    false,
+            }),
+            InputEvent::Text("héllo".to_string()),
+            InputEvent::MouseMotion { position: (12.0, 34.5), delta: (-1.0, 2.0) },
+            InputEvent::GamepadAdded { id: 300, name: "Pad".to_string(), guid: "abc".to_string() },
+            InputEvent::GamepadDown(300, GamepadButton::Touchpad),
+            InputEvent::GamepadAxis(300, GamepadAxis::TriggerRight, 0.75),
+        ]);
+        recording.push_tick(Vec::new());
+        recording.push_tick(vec![InputEvent::MouseDown(MouseButton::X2)]);
+
+        let bytes = recording.to_bytes().unwrap();
+
+        assert_eq!(InputRecording::from_bytes(&bytes).unwrap(), recording);
+        assert!(InputRecording::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(InputRecording::from_bytes(b"nope").is_err());
+
+        let mut input = Input::new();
+
+        for event in recording.get_tick(0).unwrap() {
+            event.apply(&mut input);
+        }
+
+        assert!(input.key_pressed(KeyCode::Space));
+        assert_eq!(input.get_text(), "héllo");
+        assert_eq!(input.get_mouse_position(), (12.0, 34.5));
+        assert!(input.gamepad(0).unwrap().button_pressed(GamepadButton::Touchpad));
+    }
+
+    #[test]
+    fn extra_mouse_buttons_arent_recorded_as_left() {
+        let mut recording = InputRecording::new(60.0);
+        recording.push_tick(vec![InputEvent::MouseDown(MouseButton::Unknown)]);
+
+        let replayed = InputRecording::from_bytes(&recording.to_bytes().unwrap()).unwrap();
+        let mut input = Input::new();
+
+        assert_eq!(replayed.get_tick(0).unwrap(), [InputEvent::MouseDown(MouseButton::Unknown)]);
+
+        for event in replayed.get_tick(0).unwrap() {
+            event.apply(&mut input);
+        }
+
+        assert!(input.mouse_down(MouseButton::Unknown));
+        assert!(!input.mouse_down(MouseButton::Left));
+    }
+}
@@ -0,0 +1,635 @@
+use crate::{ App, Batcher, Configuration, Error };
+use super::event::*;
+use std::path::Path;
+
+/// A state of the game, like a menu, the gameplay or a pause screen, managed by the `SceneManager`.
+///
+/// Only the scene on top of the stack is updated and receives the events.
+///
+pub trait Scene {
+    /// Called when the scene is added to the stack.
+    ///
+    fn enter(&mut self, _app: &mut App) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the scene is removed from the stack.
+    ///
+    fn exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when another scene is pushed on top of this one.
+    ///
+    fn pause(&mut self, _app: &mut App) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when this scene is back on top of the stack.
+    ///
+    fn resume(&mut self, _app: &mut App) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Do logic here, the returned action changes the stack.
+    ///
+    fn update(&mut self, app: &mut App) -> Result<SceneAction, Error>;
+
+    /// Draws the scene.
+    ///
+    fn render(&mut self, app: &mut App) -> Result<(), Error>;
+
+    /// Whether the scene below is drawn first, used by scenes that don't cover the whole window.
+    ///
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// See `Configuration::resized`.
+    ///
+    fn resized(&mut self, _app: &mut App, _width: u32, _height: u32) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_focus_changed`.
+    ///
+    fn on_focus_changed(&mut self, _app: &mut App, _focused: bool) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_key`.
+    ///
+    fn on_key(&mut self, _app: &mut App, _event: &KeyEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_text_input`.
+    ///
+    fn on_text_input(&mut self, _app: &mut App, _text: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_text_editing`.
+    ///
+    fn on_text_editing(&mut self, _app: &mut App, _composition: &Composition) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_mouse_button`.
+    ///
+    fn on_mouse_button(&mut self, _app: &mut App, _event: &MouseButtonEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_mouse_motion`.
+    ///
+    fn on_mouse_motion(&mut self, _app: &mut App, _event: &MouseMotionEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_mouse_wheel`.
+    ///
+    fn on_mouse_wheel(&mut self, _app: &mut App, _event: &MouseWheelEvent) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_file_dropped`.
+    ///
+    fn on_file_dropped(&mut self, _app: &mut App, _path: &Path) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_gamepad_connected`.
+    ///
+    fn on_gamepad_connected(&mut self, _app: &mut App, _player: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_gamepad_disconnected`.
+    ///
+    fn on_gamepad_disconnected(&mut self, _app: &mut App, _player: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// See `Configuration::on_gamepad_button`.
+    ///
+    fn on_gamepad_button(&mut self, _app: &mut App, _event: &GamepadButtonEvent) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Direction a wipe moves to.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// How the window changes from a scene to another.
+///
+/// Timed transitions cover the window during the first half of the duration,
+/// change the stack and uncover it during the second half.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Transition {
+    Instant,
+    Fade { duration: f64, color: (f32, f32, f32, f32) },
+    Wipe { duration: f64, color: (f32, f32, f32, f32), direction: WipeDirection },
+}
+
+impl Transition {
+    /// Returns the total duration in seconds.
+    ///
+    pub fn get_duration(&self) -> f64 {
+        match self {
+            Self::Instant               => 0.0,
+            Self::Fade { duration, .. } => *duration,
+            Self::Wipe { duration, .. } => *duration,
+        }
+    }
+}
+
+/// Changes requested by a scene.
+///
+pub enum SceneAction {
+    /// Keeps the current scene.
+    Continue,
+
+    /// Pauses the current scene and adds a new one on top of it.
+    Push(Box<dyn Scene>, Transition),
+
+    /// Removes the current scene and resumes the one below, closes the Application if there is none.
+    Pop(Transition),
+
+    /// Removes the current scene and adds a new one in its place.
+    Replace(Box<dyn Scene>, Transition),
+
+    /// Closes the Application.
+    Quit,
+}
+
+/// Call made to a scene when the stack changes.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Lifecycle {
+    Enter,
+    Exit,
+    Pause,
+    Resume,
+}
+
+impl Lifecycle {
+    /// Calls the matching method of the scene.
+    ///
+    fn call(self, scene: &mut dyn Scene, app: &mut App) -> Result<(), Error> {
+        match self {
+            Self::Enter  => scene.enter(app),
+            Self::Exit   => scene.exit(app),
+            Self::Pause  => scene.pause(app),
+            Self::Resume => scene.resume(app),
+        }
+    }
+}
+
+/// A transition being played.
+///
+struct ActiveTransition {
+    transition: Transition,
+    elapsed:    f64,
+    pending:    Option<SceneAction>,
+}
+
+impl ActiveTransition {
+    /// Returns how much of the window is covered, from 0 to 1.
+    ///
+    fn get_coverage(&self) -> f32 {
+        let half = self.transition.get_duration() / 2.0;
+
+        if half <= 0.0 {
+            return 0.0;
+        }
+
+        let coverage = if self.elapsed < half { self.elapsed / half } else { 2.0 - self.elapsed / half };
+
+        coverage.clamp(0.0, 1.0) as f32
+    }
+}
+
+/// `Configuration` that holds a stack of scenes.
+///
+/// ```ignore
+/// App::new(AppConfig::default())?.start(&mut SceneManager::new(Menu::new()))?;
+/// ```
+///
+pub struct SceneManager {
+    scenes:     Vec<Box<dyn Scene>>,
+    transition: Option<ActiveTransition>,
+    batcher:    Option<Batcher>,
+}
+
+impl SceneManager {
+    /// Creates a new SceneManager, the first scene enters when the Application starts.
+    ///
+    pub fn new(first: impl Scene + 'static) -> Self {
+        Self {
+            scenes:     vec![Box::new(first)],
+            transition: None,
+            batcher:    None,
+        }
+    }
+
+    /// Returns the number of scenes in the stack.
+    ///
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Returns whether the stack is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Returns whether a transition is being played.
+    ///
+    pub fn in_transition(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Plays the transition of the action, or applies it right away.
+    ///
+    fn request(&mut self, app: &mut App, action: SceneAction) -> Result<(), Error> {
+        let transition = match &action {
+            SceneAction::Push(_, transition)    => *transition,
+            SceneAction::Pop(transition)        => *transition,
+            SceneAction::Replace(_, transition) => *transition,
+
+            _ => Transition::Instant,
+        };
+
+        if transition.get_duration() <= 0.0 {
+            return self.apply(app, action);
+        }
+
+        self.transition = Some(ActiveTransition {
+            transition,
+            elapsed: 0.0,
+            pending: Some(action),
+        });
+
+        Ok(())
+    }
+
+    /// Changes the stack.
+    ///
+    fn apply(&mut self, app: &mut App, action: SceneAction) -> Result<(), Error> {
+        if self.change(action, |scene, lifecycle| lifecycle.call(scene, app))? {
+            app.close();
+        }
+
+        Ok(())
+    }
+
+    /// Changes the stack, `notify` makes the lifecycle calls. Returns whether the Application must close.
+    ///
+    fn change(&mut self, action: SceneAction, mut notify: impl FnMut(&mut dyn Scene, Lifecycle) -> Result<(), Error>) -> Result<bool, Error> {
+        match action {
+            SceneAction::Continue => (),
+
+            SceneAction::Push(mut scene, _) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    notify(top.as_mut(), Lifecycle::Pause)?;
+                }
+
+                notify(scene.as_mut(), Lifecycle::Enter)?;
+                self.scenes.push(scene);
+            },
+
+            SceneAction::Pop(_) => {
+                if let Some(mut top) = self.scenes.pop() {
+                    notify(top.as_mut(), Lifecycle::Exit)?;
+                }
+
+                match self.scenes.last_mut() {
+                    Some(top) => notify(top.as_mut(), Lifecycle::Resume)?,
+                    None => return Ok(true),
+                }
+            },
+
+            SceneAction::Replace(mut scene, _) => {
+                if let Some(mut top) = self.scenes.pop() {
+                    notify(top.as_mut(), Lifecycle::Exit)?;
+                }
+
+                notify(scene.as_mut(), Lifecycle::Enter)?;
+                self.scenes.push(scene);
+            },
+
+            SceneAction::Quit => return Ok(true),
+        }
+
+        Ok(false)
+    }
+
+    /// Advances the transition, the pending action is applied when the window is fully covered.
+    ///
+    fn update_transition(&mut self, app: &mut App) -> Result<(), Error> {
+        // Transitions ignore the time scale, they still play while the game is paused.
+        let delta_time = if app.timer.fixed_step {
+            app.timer.get_unscaled_step()
+        } else {
+            app.timer.unscaled_delta_time
+        };
+
+        let Some(active) = &mut self.transition else {
+            return Ok(());
+        };
+
+        active.elapsed += delta_time;
+
+        let duration = active.transition.get_duration();
+        let pending  = if active.elapsed >= duration / 2.0 { active.pending.take() } else { None };
+        let finished = active.elapsed >= duration;
+
+        if finished {
+            self.transition = None;
+        }
+
+        match pending {
+            Some(action) => self.apply(app, action),
+            None => Ok(()),
+        }
+    }
+
+    /// Draws the transition over the window.
+    ///
+    fn render_transition(&mut self, app: &mut App) -> Result<(), Error> {
+        let (Some(active), Some(batcher)) = (&self.transition, &mut self.batcher) else {
+            return Ok(());
+        };
+
+        let coverage = active.get_coverage();
+        let covering = active.pending.is_some();
+
+        let (width, height) = app.get_size();
+        let (width, height) = (width as f32, height as f32);
+
+        batcher.origin(app)?;
+
+        match active.transition {
+            Transition::Instant => (),
+
+            Transition::Fade { color: (r, g, b, a), .. } => {
+                batcher.set_color(r, g, b, a * coverage);
+                batcher.rectangle(0.0, 0.0, width, height);
+            },
+
+            Transition::Wipe { color: (r, g, b, a), direction, .. } => {
+                // The covered part enters from one side and leaves through the other.
+                let (x, y, w, h) = match (direction, covering) {
+                    (WipeDirection::Right, true)  => (0.0, 0.0, width * coverage, height),
+                    (WipeDirection::Right, false) => (width * (1.0 - coverage), 0.0, width * coverage, height),
+                    (WipeDirection::Left,  true)  => (width * (1.0 - coverage), 0.0, width * coverage, height),
+                    (WipeDirection::Left,  false) => (0.0, 0.0, width * coverage, height),
+                    (WipeDirection::Down,  true)  => (0.0, 0.0, width, height * coverage),
+                    (WipeDirection::Down,  false) => (0.0, height * (1.0 - coverage), width, height * coverage),
+                    (WipeDirection::Up,    true)  => (0.0, height * (1.0 - coverage), width, height * coverage),
+                    (WipeDirection::Up,    false) => (0.0, 0.0, width, height * coverage),
+                };
+
+                batcher.set_color(r, g, b, a);
+                batcher.rectangle(x, y, w, h);
+            },
+        }
+
+        batcher.present()
+    }
+}
+
+impl Configuration for SceneManager {
+    fn startup(&mut self, app: &mut App) -> Result<(), Error> {
+        self.batcher = Some(Batcher::new()?);
+
+        for scene in self.scenes.iter_mut() {
+            scene.enter(app)?;
+        }
+
+        Ok(())
+    }
+
+    fn shutdown(&mut self, app: &mut App) -> Result<(), Error> {
+        let mut result = Ok(());
+
+        // Every scene exits, even if one of them fails.
+        while let Some(mut scene) = self.scenes.pop() {
+            result = result.and(scene.exit(app));
+        }
+
+        result
+    }
+
+    fn update(&mut self, app: &mut App) -> Result<(), Error> {
+        // Scenes are frozen while a transition plays.
+        if self.transition.is_some() {
+            return self.update_transition(app);
+        }
+
+        let action = match self.scenes.last_mut() {
+            Some(scene) => scene.update(app)?,
+            None => return Ok(()),
+        };
+
+        self.request(app, action)
+    }
+
+    fn render(&mut self, app: &mut App) -> Result<(), Error> {
+        // Draws from the topmost scene that covers the whole window.
+        let first = self.scenes.iter().rposition(|scene| !scene.is_overlay()).unwrap_or(0);
+
+        for scene in self.scenes[first..].iter_mut() {
+            scene.render(app)?;
+        }
+
+        self.render_transition(app)
+    }
+
+    fn resized(&mut self, app: &mut App, width: u32, height: u32) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.resized(app, width, height),
+            None => Ok(()),
+        }
+    }
+
+    fn on_focus_changed(&mut self, app: &mut App, focused: bool) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_focus_changed(app, focused),
+            None => Ok(()),
+        }
+    }
+
+    fn on_key(&mut self, app: &mut App, event: &KeyEvent) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_key(app, event),
+            None => Ok(()),
+        }
+    }
+
+    fn on_text_input(&mut self, app: &mut App, text: &str) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_text_input(app, text),
+            None => Ok(()),
+        }
+    }
+
+    fn on_text_editing(&mut self, app: &mut App, composition: &Composition) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_text_editing(app, composition),
+            None => Ok(()),
+        }
+    }
+
+    fn on_mouse_button(&mut self, app: &mut App, event: &MouseButtonEvent) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_mouse_button(app, event),
+            None => Ok(()),
+        }
+    }
+
+    fn on_mouse_motion(&mut self, app: &mut App, event: &MouseMotionEvent) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_mouse_motion(app, event),
+            None => Ok(()),
+        }
+    }
+
+    fn on_mouse_wheel(&mut self, app: &mut App, event: &MouseWheelEvent) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_mouse_wheel(app, event),
+            None => Ok(()),
+        }
+    }
+
+    fn on_file_dropped(&mut self, app: &mut App, path: &Path) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_file_dropped(app, path),
+            None => Ok(()),
+        }
+    }
+
+    fn on_gamepad_connected(&mut self, app: &mut App, player: usize) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_gamepad_connected(app, player),
+            None => Ok(()),
+        }
+    }
+
+    fn on_gamepad_disconnected(&mut self, app: &mut App, player: usize) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_gamepad_disconnected(app, player),
+            None => Ok(()),
+        }
+    }
+
+    fn on_gamepad_button(&mut self, app: &mut App, event: &GamepadButtonEvent) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_gamepad_button(app, event),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(elapsed: f64, pending: bool) -> ActiveTransition {
+        ActiveTransition {
+            transition: Transition::Fade { duration: 1.0, color: (0.0, 0.0, 0.0, 1.0) },
+            elapsed,
+            pending:    if pending { Some(SceneAction::Continue) } else { None },
+        }
+    }
+
+    #[test]
+    fn transitions_cover_then_uncover() {
+        assert_eq!(transition(0.0,  true).get_coverage(), 0.0);
+        assert_eq!(transition(0.25, true).get_coverage(), 0.5);
+        assert_eq!(transition(0.5,  false).get_coverage(), 1.0);
+        assert_eq!(transition(0.75, false).get_coverage(), 0.5);
+        assert_eq!(transition(1.0,  false).get_coverage(), 0.0);
+    }
+
+    /// Scene doing nothing, told apart by its address.
+    ///
+    struct Named(&'static str);
+
+    impl Scene for Named {
+        fn update(&mut self, _app: &mut App) -> Result<SceneAction, Error> {
+            Ok(SceneAction::Continue)
+        }
+
+        fn render(&mut self, _app: &mut App) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    /// Names of the scenes by address, the lifecycle calls only see `dyn Scene`.
+    ///
+    #[derive(Default)]
+    struct Names(Vec<(*const (), &'static str)>);
+
+    impl Names {
+        fn scene(&mut self, name: &'static str) -> Box<dyn Scene> {
+            let scene = Box::new(Named(name));
+
+            self.0.push((id(scene.as_ref()), scene.0));
+            scene
+        }
+
+        fn get(&self, scene: &dyn Scene) -> &'static str {
+            self.0.iter().find(|(ptr, _)| *ptr == id(scene)).map(|(_, name)| *name).unwrap()
+        }
+
+        /// Applies an action without an App, returns the lifecycle calls and whether the Application closes.
+        ///
+        fn change(&self, manager: &mut SceneManager, action: SceneAction) -> (Vec<(&'static str, Lifecycle)>, bool) {
+            let mut calls = Vec::new();
+            let close     = manager.change(action, |scene, lifecycle| {
+                calls.push((self.get(scene), lifecycle));
+                Ok(())
+            }).unwrap();
+
+            (calls, close)
+        }
+    }
+
+    fn id(scene: &dyn Scene) -> *const () {
+        scene as *const dyn Scene as *const ()
+    }
+
+    #[test]
+    fn stack_changes() {
+        let mut names   = Names::default();
+        let mut manager = SceneManager { scenes: vec![names.scene("menu")], transition: None, batcher: None };
+
+        let push = SceneAction::Push(names.scene("game"), Transition::Instant);
+        assert_eq!(names.change(&mut manager, push), (vec![("menu", Lifecycle::Pause), ("game", Lifecycle::Enter)], false));
+        assert_eq!(manager.len(), 2);
+
+        let replace = SceneAction::Replace(names.scene("pause"), Transition::Instant);
+        assert_eq!(names.change(&mut manager, replace), (vec![("game", Lifecycle::Exit), ("pause", Lifecycle::Enter)], false));
+        assert_eq!(manager.len(), 2);
+
+        let pop = SceneAction::Pop(Transition::Instant);
+        assert_eq!(names.change(&mut manager, pop), (vec![("pause", Lifecycle::Exit), ("menu", Lifecycle::Resume)], false));
+        assert_eq!(manager.len(), 1);
+
+        // Popping the last scene closes the Application.
+        let pop = SceneAction::Pop(Transition::Instant);
+        assert_eq!(names.change(&mut manager, pop), (vec![("menu", Lifecycle::Exit)], true));
+        assert!(manager.is_empty());
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{ App, Error };
+use std::collections::HashSet;
+
+/// Callback ran by the Scheduler, it receives the owner of the Scheduler, the App by default.
+///
+pub(crate) type ScheduledCallback<T = App> = Box<dyn FnMut(&mut T) -> Result<(), Error>>;
+
+/// Handle of a scheduled callback, used to cancel it.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScheduleHandle(u64);
+
+/// How long a callback waits, in game seconds or in updates.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Delay {
+    Seconds(f64),
+    Ticks(u32),
+}
+
+/// A callback waiting to run.
+///
+struct Task<T> {
+    handle:    ScheduleHandle,
+    interval:  Delay,
+    remaining: Delay,
+    repeat:    bool,
+    done:      bool,
+    callback:  ScheduledCallback<T>,
+}
+
+impl<T> Task<T> {
+    /// Advances the countdown by one update lasting the given seconds, returns whether the callback is due.
+    ///
+    fn advance(&mut self, seconds: f64) -> bool {
+        let due = match &mut self.remaining {
+            Delay::Seconds(remaining) => {
+                *remaining -= seconds;
+                *remaining <= 0.0
+            },
+            Delay::Ticks(remaining) => {
+                *remaining = remaining.saturating_sub(1);
+                *remaining == 0
+            },
+        };
+
+        if due {
+            // Repeating timers keep the time left over, so they don't drift.
+            // Repeats missed within a single update are collapsed into one run instead of piling up.
+            self.remaining = match (self.remaining, self.interval) {
+                (Delay::Seconds(remaining), Delay::Seconds(interval)) if remaining + interval > 0.0 => Delay::Seconds(remaining + interval),
+                _ => self.interval,
+            };
+
+            self.done = !self.repeat;
+        }
+
+        due
+    }
+}
+
+/// Stores the callbacks scheduled through the `Timer`.
+///
+pub(crate) struct Scheduler<T = App> {
+    tasks:       Vec<Task<T>>,
+    cancelled:   HashSet<ScheduleHandle>,
+    running:     HashSet<ScheduleHandle>,
+    next_handle: u64,
+}
+
+impl<T> Default for Scheduler<T> {
+    fn default() -> Self {
+        Self {
+            tasks:       Vec::new(),
+            cancelled:   HashSet::new(),
+            running:     HashSet::new(),
+            next_handle: 0,
+        }
+    }
+}
+
+impl<T> Scheduler<T> {
+    /// Stops a callback from running again. Does nothing if it has already finished.
+    ///
+    pub(crate) fn cancel(&mut self, handle: ScheduleHandle) {
+        self.tasks.retain(|task| task.handle != handle);
+
+        // The task may be running right now, outside of the list.
+        self.cancelled.insert(handle);
+    }
+
+    /// Removes every scheduled callback.
+    ///
+    pub(crate) fn clear(&mut self) {
+        for task in self.tasks.drain(..) {
+            self.cancelled.insert(task.handle);
+        }
+
+        // The tasks being run are outside of the list too.
+        self.cancelled.extend(self.running.drain());
+    }
+
+    /// Returns the number of callbacks waiting to run, counting the ones being run.
+    ///
+    pub(crate) fn len(&self) -> usize {
+        self.tasks.len() + self.running.iter().filter(|handle| !self.cancelled.contains(handle)).count()
+    }
+
+    /// Adds a new callback.
+    ///
+    pub(crate) fn schedule(&mut self, interval: Delay, repeat: bool, callback: ScheduledCallback<T>) -> ScheduleHandle {
+        let handle = ScheduleHandle(self.next_handle);
+        self.next_handle += 1;
+
+        self.tasks.push(Task {
+            handle,
+            interval,
+            remaining: interval,
+            repeat,
+            done:      false,
+            callback,
+        });
+
+        handle
+    }
+
+    /// Advances the callbacks of the owner's Scheduler, found with `scheduler`, by one update and runs the due ones.
+    ///
+    pub(crate) fn run(owner: &mut T, scheduler: fn(&mut T) -> &mut Self, seconds: f64) -> Result<(), Error> {
+        // Callbacks need the whole owner, so the tasks are moved out while running.
+        let mut tasks  = std::mem::take(&mut scheduler(owner).tasks);
+        let mut result = Ok(());
+
+        scheduler(owner).running = tasks.iter().map(|task| task.handle).collect();
+
+        for task in tasks.iter_mut() {
+            if scheduler(owner).cancelled.contains(&task.handle) {
+                continue;
+            }
+
+            if task.advance(seconds) {
+                // A finished task no longer counts as waiting, even inside its own callback.
+                if task.done {
+                    scheduler(owner).running.remove(&task.handle);
+                }
+
+                result = (task.callback)(owner);
+
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+
+        // Puts the tasks back, followed by the ones scheduled by the callbacks.
+        let scheduler = scheduler(owner);
+
+        tasks.retain(|task| !task.done && !scheduler.cancelled.contains(&task.handle));
+        tasks.append(&mut scheduler.tasks);
+
+        scheduler.tasks = tasks;
+        scheduler.running.clear();
+        scheduler.cancelled.clear();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(interval: Delay, repeat: bool) -> Task<()> {
+        Task {
+            handle:    ScheduleHandle(0),
+            interval,
+            remaining: interval,
+            repeat,
+            done:      false,
+            callback:  Box::new(|_| Ok(())),
+        }
+    }
+
+    #[test]
+    fn one_shot_seconds() {
+        let mut task = task(Delay::Seconds(0.25), false);
+
+        assert!(!task.advance(0.1));
+        assert!(!task.advance(0.1));
+        assert!(task.advance(0.1));
+        assert!(task.done);
+    }
+
+    #[test]
+    fn repeating_ticks() {
+        let mut task = task(Delay::Ticks(2), true);
+        let fired: Vec<bool> = (0..6).map(|_| task.advance(0.0)).collect();
+
+        assert_eq!(fired, [false, true, false, true, false, true]);
+        assert!(!task.done);
+    }
+
+    /// Owner of a Scheduler standing in for the App.
+    ///
+    #[derive(Default)]
+    struct Owner {
+        scheduler: Scheduler<Owner>,
+        runs:      Vec<&'static str>,
+    }
+
+    impl Owner {
+        fn scheduler(&mut self) -> &mut Scheduler<Owner> {
+            &mut self.scheduler
+        }
+
+        fn run(&mut self) {
+            Scheduler::run(self, Owner::scheduler, 0.1).unwrap();
+        }
+    }
+
+    #[test]
+    fn cancelled_tasks_dont_run() {
+        let mut owner = Owner::default();
+
+        let first = owner.scheduler.schedule(Delay::Ticks(1), true, Box::new(|owner: &mut Owner| {
+            owner.runs.push("first");
+            Ok(())
+        }));
+
+        // The second task cancels the first one in the middle of an update, and then itself.
+        let second = owner.scheduler.schedule(Delay::Ticks(1), true, Box::new(move |owner: &mut Owner| {
+            owner.runs.push("second");
+            owner.scheduler.cancel(first);
+            Ok(())
+        }));
+
+        owner.scheduler.schedule(Delay::Ticks(1), false, Box::new(move |owner: &mut Owner| {
+            owner.scheduler.cancel(second);
+            Ok(())
+        }));
+
+        owner.run();
+        assert_eq!(owner.runs, ["first", "second"]);
+        assert_eq!(owner.scheduler.len(), 0);
+
+        owner.run();
+        assert_eq!(owner.runs, ["first", "second"]);
+    }
+
+    #[test]
+    fn clearing_from_a_callback_cancels_every_task() {
+        let mut owner = Owner::default();
+
+        owner.scheduler.schedule(Delay::Ticks(1), true, Box::new(|owner: &mut Owner| {
+            owner.runs.push("first");
+            Ok(())
+        }));
+
+        owner.scheduler.schedule(Delay::Ticks(1), true, Box::new(|owner: &mut Owner| {
+            owner.runs.push("clear");
+            assert_eq!(owner.scheduler.len(), 3);
+
+            owner.scheduler.clear();
+            assert_eq!(owner.scheduler.len(), 0);
+            Ok(())
+        }));
+
+        owner.scheduler.schedule(Delay::Ticks(1), true, Box::new(|owner: &mut Owner| {
+            owner.runs.push("last");
+            Ok(())
+        }));
+
+        owner.run();
+        assert_eq!(owner.runs, ["first", "clear"]);
+        assert_eq!(owner.scheduler.len(), 0);
+
+        owner.run();
+        assert_eq!(owner.runs, ["first", "clear"]);
+    }
+
+    #[test]
+    fn tasks_scheduled_by_callbacks_run_later() {
+        let mut owner = Owner::default();
+
+        owner.scheduler.schedule(Delay::Ticks(1), false, Box::new(|owner: &mut Owner| {
+            owner.runs.push("outer");
+            owner.scheduler.schedule(Delay::Ticks(1), false, Box::new(|owner: &mut Owner| {
+                owner.runs.push("inner");
+                Ok(())
+            }));
+            Ok(())
+        }));
+
+        owner.run();
+        assert_eq!(owner.runs, ["outer"]);
+        assert_eq!(owner.scheduler.len(), 1);
+
+        owner.run();
+        assert_eq!(owner.runs, ["outer", "inner"]);
+        assert_eq!(owner.scheduler.len(), 0);
+    }
+
+    #[test]
+    fn repeats_keep_the_left_over_time() {
+        let mut task = task(Delay::Seconds(0.25), true);
+
+        assert!(!task.advance(0.125));
+        assert!(task.advance(0.25));
+        assert_eq!(task.remaining, Delay::Seconds(0.125));
+    }
+
+    #[test]
+    fn missed_repeats_are_collapsed() {
+        let mut task = task(Delay::Seconds(0.01), true);
+
+        // The interval is shorter than the update, the callback runs once per update without a backlog.
+        assert!(task.advance(0.1));
+        assert_eq!(task.remaining, Delay::Seconds(0.01));
+
+        assert!(task.advance(0.1));
+        assert_eq!(task.remaining, Delay::Seconds(0.01));
+    }
+}
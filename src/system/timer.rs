@@ -1,78 +1,117 @@
-use std::time::{ SystemTime, UNIX_EPOCH};
+use super::{ App, Clock, MonotonicClock };
+use super::schedule::{ Delay, Scheduler, ScheduleHandle };
+use crate::Error;
+use std::time::Duration;
+
+/// Framerates the delta time is snapped to by default.
+///
+const DEFAULT_SNAP_RATES: [f64; 4] = [120.0, 60.0, 30.0, 15.0];
 
 /// Structure that provides timing functionality.
 ///
 pub struct Timer {
+    clock: Box<dyn Clock>,
+
     elapsed:          f64,
-    previous_elapsed: f64,
+    previous_elapsed: Duration,
+
+    pub delta_time:          f64,
+    pub unscaled_delta_time: f64,
 
-    pub delta_time:        f64,
     pub frame_rate:        f64,
     pub frame_accumulator: f64,
     pub frame_counter:     u32,
 
     fps:        u32,
     fps_update: f64,
+
+    snap_rates:     Vec<f64>,
+    snap_tolerance: f64,
+
+    time_scale: f64,
+    paused:     bool,
+    hit_stop:   f64,
+
+    pub(crate) fixed_step: bool,
+    pub(crate) lockstep:   bool,
+    pub(crate) scheduler:  Scheduler,
 }
 
 impl Timer {
-    /// Creates a new Timer.
+    /// Creates a new Timer using the monotonic clock of the PC.
     ///
     pub fn new(frame_rate: f64) -> Self {
+        Self::with_clock(frame_rate, MonotonicClock::new())
+    }
+
+    /// Creates a new Timer reading the time from the given clock.
+    ///
+    pub fn with_clock(frame_rate: f64, clock: impl Clock + 'static) -> Self {
+        let start = clock.now();
+
         Self {
-            elapsed:          Self::get_time(),
-            previous_elapsed: Self::get_time(),
+            clock: Box::new(clock),
+
+            elapsed:          start.as_secs_f64(),
+            previous_elapsed: start,
+
+            delta_time:          0.0,
+            unscaled_delta_time: 0.0,
 
-            delta_time: 0.0,
             frame_rate,
             frame_accumulator: 0.0,
             frame_counter:     0,
 
             fps:        0,
-            fps_update: 0.0,
+            fps_update: start.as_secs_f64(),
+
+            snap_rates:     DEFAULT_SNAP_RATES.to_vec(),
+            snap_tolerance: 0.0002,
+
+            time_scale: 1.0,
+            paused:     false,
+            hit_stop:   0.0,
+
+            fixed_step: true,
+            lockstep:   false,
+            scheduler:  Scheduler::default(),
         }
     }
 
     /// Updates the time between two frames.
     ///
     pub fn update(&mut self) {
-        self.elapsed          = Self::get_time();
-        self.delta_time       = self.elapsed - self.previous_elapsed;
-        self.previous_elapsed = self.elapsed;
+        // Durations are subtracted before the conversion, so equal steps give equal deltas.
+        let now = self.clock.now();
 
-        // Handle unexpected anomalies like overflow, extremely slow frames, etc.
-        if self.delta_time >= 1.0 / self.frame_rate * 8.0 {
-            self.delta_time = 1.0 / self.frame_rate;
-        }
+        self.elapsed             = now.as_secs_f64();
+        self.unscaled_delta_time = now.saturating_sub(self.previous_elapsed).as_secs_f64();
+        self.previous_elapsed    = now;
 
-        if self.delta_time < 0.0 {
-            self.delta_time = 0.0;
+        // Headless Applications take exactly one frame per update, whatever the clock rounding.
+        if self.lockstep {
+            self.unscaled_delta_time = 1.0 / self.frame_rate;
         }
 
         // Snaps the delta time to a nice framerate.
-        if (self.delta_time - 1.0/120.0).abs() < 0.0002 {
-            self.delta_time = 1.0/120.0;
+        for rate in self.snap_rates.iter() {
+            if (self.unscaled_delta_time - 1.0 / rate).abs() < self.snap_tolerance {
+                self.unscaled_delta_time = 1.0 / rate;
+            }
         }
 
-        if (self.delta_time - 1.0/60.0).abs() < 0.0002 {
-            self.delta_time = 1.0/60.0;
+        // Variable steps consume the whole delta time in a single update.
+        // Fixed steps keep all of it, `take_steps` limits how many updates catch up.
+        if self.fixed_step {
+            self.frame_accumulator += self.unscaled_delta_time;
         }
 
-        if (self.delta_time - 1.0/30.0).abs() < 0.0002 {
-            self.delta_time = 1.0/30.0;
-        }
-
-        if (self.delta_time - 1.0/15.0).abs() < 0.0002 {
-            self.delta_time = 1.0/15.0;
+        // Handle unexpected anomalies like overflow, extremely slow frames, etc.
+        if self.unscaled_delta_time >= 1.0 / self.frame_rate * 8.0 {
+            self.unscaled_delta_time = 1.0 / self.frame_rate;
         }
 
-        self.frame_accumulator += self.delta_time;
-
-        // Prevent from crashes.
-        if self.frame_accumulator >= 1.0 / self.frame_rate * 8.0 {
-            self.delta_time        = 1.0 / self.frame_rate;
-            self.frame_accumulator = 0.0;
-        }
+        self.delta_time = self.scale(self.unscaled_delta_time);
 
         // Updates the FPS.
         if self.elapsed - self.fps_update > 1.0 {
@@ -82,17 +121,337 @@ impl Timer {
         }
     }
 
+    /// Forgets the time since the last update, used while updates are skipped.
+    /// The next update only measures the time after this call, and no fixed step is pending.
+    ///
+    pub(crate) fn resync(&mut self) {
+        self.previous_elapsed  = self.clock.now();
+        self.frame_accumulator = 0.0;
+    }
+
+    /// Consumes the accumulated time in fixed steps and returns how many updates to run.
+    /// When more than `max_steps` are missing, the rest is dropped but the fraction is kept for interpolation.
+    ///
+    pub(crate) fn take_steps(&mut self, max_steps: u32) -> u32 {
+        let step = 1.0 / self.frame_rate;
+        let mut steps = 0;
+
+        while self.frame_accumulator >= step {
+            // Too far behind, drops the missing updates.
+            if steps >= max_steps.max(1) {
+                self.frame_accumulator %= step;
+                break;
+            }
+
+            self.frame_accumulator -= step;
+            steps += 1;
+        }
+
+        steps
+    }
+
+    /// Applies the pause, the hit-stop and the time scale to a real delta time.
+    ///
+    fn scale(&mut self, delta_time: f64) -> f64 {
+        if self.paused {
+            return 0.0;
+        }
+
+        // The hit-stop may end in the middle of the frame, only that part of the frame is frozen.
+        let frozen = self.hit_stop.min(delta_time);
+
+        self.hit_stop -= frozen;
+
+        (delta_time - frozen) * self.time_scale
+    }
+
+    /// Returns the duration of a fixed update after the time scale, zero while paused or in a hit-stop.
+    /// Use it to move things inside `Configuration::update`.
+    ///
+    pub fn get_step(&self) -> f64 {
+        if self.paused || self.hit_stop > 0.0 {
+            return 0.0;
+        }
+
+        self.time_scale / self.frame_rate
+    }
+
+    /// Returns the game time covered by one call of `Configuration::update`.
+    /// It is the fixed step when the loop uses one, otherwise the scaled delta time of the frame.
+    ///
+    pub fn get_update_delta(&self) -> f64 {
+        if self.fixed_step {
+            self.get_step()
+        } else {
+            self.delta_time
+        }
+    }
+
+    /// Returns the real duration of a fixed update, for things like UI that ignore the time scale.
+    ///
+    pub fn get_unscaled_step(&self) -> f64 {
+        1.0 / self.frame_rate
+    }
+
+    /// Sets the speed of the game time, 1 is the normal speed and 0.5 is slow-motion.
+    ///
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    /// Returns the speed of the game time.
+    ///
+    pub fn get_time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Stops the game time, the scaled delta time becomes zero until resumed.
+    ///
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the game time.
+    ///
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns whether the game time is paused.
+    ///
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Freezes the game time for the given number of real seconds, used to give weight to hits.
+    /// Calling it during another hit-stop keeps the longest one.
+    ///
+    pub fn hit_stop(&mut self, seconds: f64) {
+        self.hit_stop = self.hit_stop.max(seconds);
+    }
+
+    /// Returns whether the game time is frozen by a hit-stop.
+    ///
+    pub fn in_hit_stop(&self) -> bool {
+        self.hit_stop > 0.0
+    }
+
+    /// Returns how far the Application is between the last update and the next one, from 0 to 1.
+    /// Used to interpolate the rendering between two fixed updates, see `Interpolated`.
+    /// It is always 1 when the loop is not using a fixed step.
+    ///
+    pub fn get_alpha(&self) -> f64 {
+        if !self.fixed_step {
+            return 1.0;
+        }
+
+        (self.frame_accumulator * self.frame_rate).clamp(0.0, 1.0)
+    }
+
+    /// Runs the callback once after the given number of seconds of game time.
+    ///
+    /// Scheduled callbacks run after `Configuration::update`, so they are paused and slowed down with the game time.
+    ///
+    pub fn after(&mut self, seconds: f64, callback: impl FnMut(&mut App) -> Result<(), Error> + 'static) -> ScheduleHandle {
+        self.scheduler.schedule(Delay::Seconds(seconds), false, Box::new(callback))
+    }
+
+    /// Runs the callback every given number of seconds of game time.
+    /// It runs at most once per update, repeats missed during a single update are not made up for.
+    ///
+    pub fn every(&mut self, seconds: f64, callback: impl FnMut(&mut App) -> Result<(), Error> + 'static) -> ScheduleHandle {
+        self.scheduler.schedule(Delay::Seconds(seconds), true, Box::new(callback))
+    }
+
+    /// Runs the callback once after the given number of updates.
+    /// Updates are counted even while the game time is paused.
+    ///
+    pub fn after_ticks(&mut self, ticks: u32, callback: impl FnMut(&mut App) -> Result<(), Error> + 'static) -> ScheduleHandle {
+        self.scheduler.schedule(Delay::Ticks(ticks), false, Box::new(callback))
+    }
+
+    /// Runs the callback every given number of updates.
+    ///
+    pub fn every_ticks(&mut self, ticks: u32, callback: impl FnMut(&mut App) -> Result<(), Error> + 'static) -> ScheduleHandle {
+        self.scheduler.schedule(Delay::Ticks(ticks), true, Box::new(callback))
+    }
+
+    /// Stops a scheduled callback, does nothing if it has already finished.
+    ///
+    pub fn cancel(&mut self, handle: ScheduleHandle) {
+        self.scheduler.cancel(handle);
+    }
+
+    /// Stops every scheduled callback.
+    ///
+    pub fn cancel_all(&mut self) {
+        self.scheduler.clear();
+    }
+
+    /// Returns the number of callbacks waiting to run.
+    ///
+    pub fn get_scheduled_count(&self) -> usize {
+        self.scheduler.len()
+    }
+
     /// Returns the current number of frames per second.
     ///
     pub fn get_fps(&mut self) -> u32 {
         self.fps
     }
-    
-    /// Returns the actual time based in the PC Clock.
+
+    /// Returns the time of the last update in seconds, relative to the start of the clock.
+    ///
+    pub fn get_elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Returns the current time of the clock in seconds.
     ///
-    pub fn get_time() -> f64 {
-        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    pub fn get_time(&self) -> f64 {
+        self.clock.now().as_secs_f64()
+    }
 
-        time.as_secs_f64()
+    /// Sets the framerates the delta time is snapped to when it is close enough to them.
+    /// Snapping hides the jitter of the clock, an empty list disables it.
+    ///
+    pub fn set_snap_rates(&mut self, rates: &[f64]) {
+        self.snap_rates = rates.to_vec();
     }
-}
\ No newline at end of file
+
+    /// Sets how close, in seconds, the delta time must be to a snap rate to be snapped.
+    ///
+    pub fn set_snap_tolerance(&mut self, tolerance: f64) {
+        self.snap_tolerance = tolerance;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::ManualClock;
+
+    #[test]
+    fn manual_clock_drives_the_timer() {
+        let clock     = ManualClock::new();
+        let mut timer = Timer::with_clock(60.0, clock.clone());
+
+        clock.advance_secs(0.01);
+        timer.update();
+
+        assert_eq!(timer.delta_time, 0.01);
+        assert_eq!(timer.get_elapsed(), 0.01);
+        assert_eq!(timer.frame_accumulator, 0.01);
+    }
+
+    #[test]
+    fn snapping_can_be_disabled() {
+        let clock     = ManualClock::new();
+        let mut timer = Timer::with_clock(60.0, clock.clone());
+
+        clock.advance_secs(0.0167);
+        timer.update();
+        assert_eq!(timer.delta_time, 1.0 / 60.0);
+
+        timer.set_snap_rates(&[]);
+
+        clock.advance_secs(0.0167);
+        timer.update();
+        assert!((timer.delta_time - 0.0167).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fixed_steps_catch_up_to_max_steps() {
+        let clock     = ManualClock::new();
+        let mut timer = Timer::with_clock(64.0, clock.clone());
+
+        timer.set_snap_rates(&[]);
+
+        // A frame 10 steps long runs all of them.
+        clock.advance_secs(10.0 / 64.0);
+        timer.update();
+        assert_eq!(timer.take_steps(12), 10);
+
+        // A longer frame runs 12 steps and drops the rest.
+        clock.advance_secs(20.5 / 64.0);
+        timer.update();
+        assert_eq!(timer.take_steps(12), 12);
+        assert_eq!(timer.frame_accumulator, 0.5 / 64.0);
+    }
+
+    #[test]
+    fn lockstep_runs_one_update_per_frame() {
+        let clock     = ManualClock::new();
+        let mut timer = Timer::with_clock(144.0, clock.clone());
+        let mut steps = 0;
+
+        timer.lockstep = true;
+
+        // The clock is rounded to whole nanoseconds, 144 Hz is not snapped.
+        for _ in 0..100 {
+            clock.advance_secs(1.0 / 144.0);
+            timer.update();
+            steps += timer.take_steps(8);
+        }
+
+        assert_eq!(steps, 100);
+        assert_eq!(timer.frame_accumulator, 0.0);
+    }
+
+    #[test]
+    fn resync_skips_the_time_away() {
+        let clock     = ManualClock::new();
+        let mut timer = Timer::with_clock(64.0, clock.clone());
+
+        timer.set_snap_rates(&[]);
+
+        // Half a step is pending when the updates stop for 5 seconds.
+        clock.advance_secs(1.5 / 64.0);
+        timer.update();
+        assert_eq!(timer.take_steps(8), 1);
+
+        clock.advance_secs(5.0);
+        timer.resync();
+
+        clock.advance_secs(1.0 / 64.0);
+        timer.update();
+        assert_eq!(timer.take_steps(8), 1);
+        assert_eq!(timer.frame_accumulator, 0.0);
+    }
+
+    #[test]
+    fn time_scale_pause_and_hit_stop() {
+        let clock     = ManualClock::new();
+        let mut timer = Timer::with_clock(50.0, clock.clone());
+
+        timer.set_snap_rates(&[]);
+        timer.set_time_scale(0.5);
+
+        clock.advance_secs(0.02);
+        timer.update();
+        assert_eq!(timer.delta_time, 0.01);
+        assert_eq!(timer.unscaled_delta_time, 0.02);
+        assert_eq!(timer.get_step(), 0.01);
+
+        timer.pause();
+        clock.advance_secs(0.02);
+        timer.update();
+        assert_eq!(timer.delta_time, 0.0);
+        assert_eq!(timer.unscaled_delta_time, 0.02);
+
+        // The hit-stop ends in the middle of the second frame.
+        timer.resume();
+        timer.set_time_scale(1.0);
+        timer.hit_stop(0.03);
+
+        clock.advance_secs(0.02);
+        timer.update();
+        assert_eq!(timer.delta_time, 0.0);
+        assert!(timer.in_hit_stop());
+
+        clock.advance_secs(0.02);
+        timer.update();
+        assert!((timer.delta_time - 0.01).abs() < 1e-9);
+        assert!(!timer.in_hit_stop());
+    }
+}
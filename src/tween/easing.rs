@@ -0,0 +1,210 @@
+use std::f32::consts::PI;
+
+/// Easing curves by Robert Penner, they map the progress of a tween to the progress of the value.
+///
+/// `In` curves start slow, `Out` curves end slow and `InOut` curves do both.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Ease {
+    #[default]
+    Linear,
+
+    InQuad,
+    OutQuad,
+    InOutQuad,
+
+    InCubic,
+    OutCubic,
+    InOutCubic,
+
+    InQuart,
+    OutQuart,
+    InOutQuart,
+
+    InQuint,
+    OutQuint,
+    InOutQuint,
+
+    InSine,
+    OutSine,
+    InOutSine,
+
+    InExpo,
+    OutExpo,
+    InOutExpo,
+
+    InCirc,
+    OutCirc,
+    InOutCirc,
+
+    InBack,
+    OutBack,
+    InOutBack,
+
+    InElastic,
+    OutElastic,
+    InOutElastic,
+
+    InBounce,
+    OutBounce,
+    InOutBounce,
+}
+
+/// How far the back curves overshoot.
+///
+const BACK: f32 = 1.70158;
+
+impl Ease {
+    /// Returns the eased progress, `t` is clamped between 0 and 1.
+    /// The back and elastic curves go outside of that range before settling.
+    ///
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => t,
+
+            Self::InQuad    => t.powi(2),
+            Self::OutQuad   => out(t, |t| t.powi(2)),
+            Self::InOutQuad => in_out(t, |t| t.powi(2)),
+
+            Self::InCubic    => t.powi(3),
+            Self::OutCubic   => out(t, |t| t.powi(3)),
+            Self::InOutCubic => in_out(t, |t| t.powi(3)),
+
+            Self::InQuart    => t.powi(4),
+            Self::OutQuart   => out(t, |t| t.powi(4)),
+            Self::InOutQuart => in_out(t, |t| t.powi(4)),
+
+            Self::InQuint    => t.powi(5),
+            Self::OutQuint   => out(t, |t| t.powi(5)),
+            Self::InOutQuint => in_out(t, |t| t.powi(5)),
+
+            Self::InSine    => in_sine(t),
+            Self::OutSine   => out(t, in_sine),
+            Self::InOutSine => in_out(t, in_sine),
+
+            Self::InExpo    => in_expo(t),
+            Self::OutExpo   => out(t, in_expo),
+            Self::InOutExpo => in_out(t, in_expo),
+
+            Self::InCirc    => in_circ(t),
+            Self::OutCirc   => out(t, in_circ),
+            Self::InOutCirc => in_out(t, in_circ),
+
+            Self::InBack    => in_back(t, BACK),
+            Self::OutBack   => out(t, |t| in_back(t, BACK)),
+            Self::InOutBack => in_out(t, |t| in_back(t, BACK * 1.525)),
+
+            Self::InElastic    => in_elastic(t, 3.0),
+            Self::OutElastic   => out(t, |t| in_elastic(t, 3.0)),
+            Self::InOutElastic => in_out(t, |t| in_elastic(t, 4.5)),
+
+            Self::InBounce    => out(t, out_bounce),
+            Self::OutBounce   => out_bounce(t),
+            Self::InOutBounce => in_out(t, |t| 1.0 - out_bounce(1.0 - t)),
+        }
+    }
+}
+
+/// Turns an `In` curve into its `Out` version.
+///
+fn out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    1.0 - ease_in(1.0 - t)
+}
+
+/// Turns an `In` curve into its `InOut` version.
+///
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+fn in_sine(t: f32) -> f32 {
+    1.0 - (t * PI / 2.0).cos()
+}
+
+fn in_expo(t: f32) -> f32 {
+    if t <= 0.0 { 0.0 } else { 2.0_f32.powf(10.0 * t - 10.0) }
+}
+
+fn in_circ(t: f32) -> f32 {
+    1.0 - (1.0 - t * t).sqrt()
+}
+
+/// The `InOut` curve overshoots more, like in Penner's equations, since each half only covers half of the way.
+///
+fn in_back(t: f32, overshoot: f32) -> f32 {
+    (overshoot + 1.0) * t.powi(3) - overshoot * t.powi(2)
+}
+
+/// The `InOut` curve uses a longer period, like in Penner's equations, so each half keeps the same swing.
+///
+fn in_elastic(t: f32, period: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+
+    -(2.0_f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.0 - period / 4.0) * (2.0 * PI / period)).sin()
+}
+
+fn out_bounce(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_start_and_end_in_place() {
+        let curves = [
+            Ease::Linear, Ease::InQuad, Ease::OutQuad, Ease::InOutQuad, Ease::InCubic, Ease::OutCubic,
+            Ease::InOutCubic, Ease::InQuart, Ease::OutQuart, Ease::InOutQuart, Ease::InQuint, Ease::OutQuint,
+            Ease::InOutQuint, Ease::InSine, Ease::OutSine, Ease::InOutSine, Ease::InExpo, Ease::OutExpo,
+            Ease::InOutExpo, Ease::InCirc, Ease::OutCirc, Ease::InOutCirc, Ease::InBack, Ease::OutBack,
+            Ease::InOutBack, Ease::InElastic, Ease::OutElastic, Ease::InOutElastic, Ease::InBounce,
+            Ease::OutBounce, Ease::InOutBounce,
+        ];
+
+        for ease in curves {
+            assert!(ease.apply(0.0).abs() < 1e-3, "{:?} doesn't start at 0", ease);
+            assert!((ease.apply(1.0) - 1.0).abs() < 1e-3, "{:?} doesn't end at 1", ease);
+        }
+
+        assert_eq!(Ease::InQuad.apply(0.5), 0.25);
+        assert_eq!(Ease::OutQuad.apply(0.5), 0.75);
+        assert_eq!(Ease::InOutCubic.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn in_out_curves_match_penner() {
+        let points = [
+            (Ease::InOutBack,    0.25, -0.099682), (Ease::InOutBack,    0.4,  0.089926),
+            (Ease::InOutBack,    0.6,   0.910074), (Ease::InOutBack,    0.75, 1.099682),
+            (Ease::InOutElastic, 0.25,  0.011969), (Ease::InOutElastic, 0.4, -0.117462),
+            (Ease::InOutElastic, 0.6,   1.117462), (Ease::InOutElastic, 0.75, 0.988031),
+        ];
+
+        for (ease, t, value) in points {
+            assert!((ease.apply(t) - value).abs() < 1e-4, "{:?} at {} is {}, not {}", ease, t, ease.apply(t), value);
+        }
+    }
+}
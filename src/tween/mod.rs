@@ -0,0 +1,8 @@
+pub mod easing;
+pub use easing::Ease;
+
+pub mod value;
+pub use value::Tween;
+
+pub mod sequence;
+pub use sequence::Sequence;
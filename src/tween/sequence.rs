@@ -0,0 +1,108 @@
+use super::Tween;
+use crate::{ Lerp, Timer };
+
+/// Plays tweens of the same value one after another.
+///
+/// The time left over by a tween is given to the next one, so the chain doesn't drift.
+///
+pub struct Sequence<T: Lerp + Copy> {
+    tweens:  Vec<Tween<T>>,
+    current: usize,
+}
+
+impl<T: Lerp + Copy> Sequence<T> {
+    /// Creates a new Sequence starting with the given tween.
+    ///
+    pub fn new(first: Tween<T>) -> Self {
+        Self {
+            tweens:  vec![first],
+            current: 0,
+        }
+    }
+
+    /// Adds a tween to the end of the Sequence.
+    ///
+    pub fn then(mut self, tween: Tween<T>) -> Self {
+        self.tweens.push(tween);
+        self
+    }
+
+    /// Holds the last value for the given seconds.
+    ///
+    pub fn wait(self, seconds: f64) -> Self {
+        let value = self.tweens[self.tweens.len() - 1].end_value();
+
+        self.then(Tween::new(value, value, seconds))
+    }
+
+    /// Advances the Sequence by the given seconds and returns the new value.
+    ///
+    pub fn update(&mut self, delta_time: f64) -> T {
+        let mut delta_time = delta_time;
+
+        loop {
+            let tween    = &mut self.tweens[self.current];
+            let leftover = tween.advance(delta_time);
+
+            if !tween.is_finished() || self.current + 1 == self.tweens.len() {
+                break;
+            }
+
+            self.current += 1;
+            delta_time    = leftover;
+        }
+
+        self.value()
+    }
+
+    /// Advances the Sequence by the game time covered by the current update, see `Timer::get_update_delta`.
+    ///
+    pub fn tick(&mut self, timer: &Timer) -> T {
+        self.update(timer.get_update_delta())
+    }
+
+    /// Returns the current value.
+    ///
+    pub fn value(&self) -> T {
+        self.tweens[self.current].value()
+    }
+
+    /// Returns whether the last tween has ended.
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.tweens[self.current].is_finished() && self.current + 1 == self.tweens.len()
+    }
+
+    /// Rewinds every tween to the start.
+    ///
+    pub fn reset(&mut self) {
+        for tween in self.tweens.iter_mut() {
+            tween.reset();
+        }
+
+        self.current = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leftover_time_goes_to_the_next_tween() {
+        let mut sequence = Sequence::new(Tween::new((0.0, 0.0), (10.0, 0.0), 1.0))
+            .wait(0.5)
+            .then(Tween::new((10.0, 0.0), (10.0, 20.0), 1.0));
+
+        assert_eq!(sequence.update(0.5), (5.0, 0.0));
+        assert_eq!(sequence.update(1.0), (10.0, 0.0));
+        assert_eq!(sequence.update(0.5), (10.0, 10.0));
+        assert!(!sequence.is_finished());
+
+        assert_eq!(sequence.update(2.0), (10.0, 20.0));
+        assert!(sequence.is_finished());
+
+        sequence.reset();
+        assert_eq!(sequence.value(), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,228 @@
+use super::Ease;
+use crate::{ Lerp, Timer };
+
+/// Animates a value from one point to another over time.
+///
+/// Works with anything that implements `Lerp`, like `f32`, positions and `(r, g, b, a)` colors.
+///
+/// ```ignore
+/// let mut fade = Tween::new(0.0, 1.0, 0.5).ease(Ease::OutQuad).delay(0.2);
+///
+/// let alpha = fade.tick(&app.timer);
+/// batcher.set_color(1.0, 1.0, 1.0, alpha);
+/// ```
+///
+pub struct Tween<T: Lerp + Copy> {
+    from:     T,
+    to:       T,
+    duration: f64,
+    delay:    f64,
+    ease:     Ease,
+    repeat:   Option<u32>,
+    yoyo:     bool,
+
+    elapsed:  f64,
+    waited:   f64,
+    play:     u32,
+    finished: bool,
+
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl<T: Lerp + Copy> Tween<T> {
+    /// Creates a new linear Tween lasting the given seconds.
+    ///
+    pub fn new(from: T, to: T, duration: f64) -> Self {
+        Self {
+            from,
+            to,
+            duration: duration.max(0.0),
+            delay:    0.0,
+            ease:     Ease::Linear,
+            repeat:   Some(0),
+            yoyo:     false,
+
+            elapsed:  0.0,
+            waited:   0.0,
+            play:     0,
+            finished: false,
+
+            on_complete: None,
+        }
+    }
+
+    /// Sets the easing curve.
+    ///
+    pub fn ease(mut self, ease: Ease) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    /// Waits the given seconds before starting.
+    ///
+    pub fn delay(mut self, seconds: f64) -> Self {
+        self.delay = seconds.max(0.0);
+        self
+    }
+
+    /// Plays the Tween again the given number of times after the first play.
+    ///
+    pub fn repeat(mut self, times: u32) -> Self {
+        self.repeat = Some(times);
+        self
+    }
+
+    /// Plays the Tween until it is stopped, it never completes.
+    ///
+    pub fn repeat_forever(mut self) -> Self {
+        self.repeat = None;
+        self
+    }
+
+    /// Plays every other repetition backwards, going back and forth.
+    ///
+    pub fn yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+
+    /// Sets a function called once when the last repetition ends.
+    ///
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    /// Advances the Tween by the given seconds and returns the new value.
+    ///
+    pub fn update(&mut self, delta_time: f64) -> T {
+        self.advance(delta_time);
+        self.value()
+    }
+
+    /// Advances the Tween by the game time covered by the current update, see `Timer::get_update_delta`.
+    ///
+    pub fn tick(&mut self, timer: &Timer) -> T {
+        self.update(timer.get_update_delta())
+    }
+
+    /// Advances the Tween and returns the time left over after its end, used to chain tweens.
+    ///
+    pub(crate) fn advance(&mut self, delta_time: f64) -> f64 {
+        if self.finished {
+            return delta_time;
+        }
+
+        // The delay only happens once, repetitions follow each other.
+        let wait = (self.delay - self.waited).clamp(0.0, delta_time);
+
+        self.waited += wait;
+
+        if self.waited < self.delay {
+            return 0.0;
+        }
+
+        self.elapsed += delta_time - wait;
+
+        while self.elapsed >= self.duration {
+            if matches!(self.repeat, Some(times) if self.play >= times) {
+                let leftover = self.elapsed - self.duration;
+
+                self.elapsed  = self.duration;
+                self.finished = true;
+
+                if let Some(callback) = &mut self.on_complete {
+                    callback();
+                }
+
+                return leftover;
+            }
+
+            self.elapsed -= self.duration;
+            self.play    += 1;
+
+            // Avoids an endless loop on instant tweens that repeat forever.
+            if self.duration <= 0.0 {
+                break;
+            }
+        }
+
+        0.0
+    }
+
+    /// Returns the current value.
+    ///
+    pub fn value(&self) -> T {
+        let mut t = if self.duration > 0.0 { self.elapsed / self.duration } else { 1.0 };
+
+        if self.yoyo && self.play % 2 == 1 {
+            t = 1.0 - t;
+        }
+
+        self.from.lerp(&self.to, self.ease.apply(t as f32))
+    }
+
+    /// Returns the value the Tween stops at, after the last repetition.
+    ///
+    pub fn end_value(&self) -> T {
+        match self.repeat {
+            Some(times) if self.yoyo && times % 2 == 1 => self.from,
+            _ => self.to,
+        }
+    }
+
+    /// Returns the progress of the current repetition, from 0 to 1.
+    ///
+    pub fn progress(&self) -> f64 {
+        if self.duration > 0.0 { self.elapsed / self.duration } else { 1.0 }
+    }
+
+    /// Returns whether the last repetition has ended.
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Rewinds the Tween to the start, including the delay.
+    ///
+    pub fn reset(&mut self) {
+        self.elapsed  = 0.0;
+        self.waited   = 0.0;
+        self.play     = 0;
+        self.finished = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn delay_repeat_and_yoyo() {
+        let completed = Rc::new(Cell::new(0));
+        let counter   = completed.clone();
+
+        let mut tween = Tween::new(0.0_f32, 10.0, 1.0)
+            .delay(0.5)
+            .repeat(1)
+            .yoyo(true)
+            .on_complete(move || counter.set(counter.get() + 1));
+
+        assert_eq!(tween.update(0.25), 0.0);
+        assert_eq!(tween.update(0.75), 5.0);
+        assert_eq!(tween.update(0.75), 7.5);
+        assert_eq!(tween.update(0.25), 5.0);
+        assert!(!tween.is_finished());
+
+        assert_eq!(tween.advance(0.75), 0.25);
+        assert_eq!(tween.value(), 0.0);
+        assert_eq!(tween.end_value(), 0.0);
+        assert!(tween.is_finished());
+        assert_eq!(completed.get(), 1);
+
+        tween.update(1.0);
+        assert_eq!(completed.get(), 1);
+    }
+}